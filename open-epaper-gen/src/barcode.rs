@@ -0,0 +1,427 @@
+//! Encoding of one-dimensional barcodes.
+//!
+//! A barcode is a row of equally wide modules, each of which is either dark (a
+//! bar) or light (a space). Wider bars and spaces are simply runs of several
+//! modules. This module turns data into those modules for a [Symbology]; the
+//! [Barcode](crate::draw::Barcode) view draws them.
+//!
+//! The retail symbologies (EAN-13, UPC-A, and EAN-8) only encode digits and end
+//! with a check digit. It can either be left out, in which case it's computed,
+//! or included, in which case it's verified. Code 128 encodes printable ASCII
+//! and Code 39 encodes upper case letters, digits, and a few symbols.
+
+use anyhow::{Result, bail};
+use std::cmp;
+
+/// The barcode formats we're able to encode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symbology {
+    /// The 13 digit European (and international) article number.
+    Ean13,
+    /// The 12 digit North American product code.
+    UpcA,
+    /// The short 8 digit article number for small packages.
+    Ean8,
+    /// Code 128, for arbitrary printable ASCII text.
+    Code128,
+    /// Code 39, for upper case letters, digits, and `-. $/+%`.
+    Code39,
+}
+
+/// A barcode, ready to be drawn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Encoded {
+    /// Whether each module is dark, from left to right. This doesn't include
+    /// the quiet zone.
+    pub modules: Vec<bool>,
+    /// The minimum number of light modules scanners need on either side of the
+    /// code.
+    pub quiet_zone: u32,
+    /// The human-readable text printed underneath the bars.
+    pub text: String,
+}
+
+/// Encode `data` using the given symbology.
+pub fn encode(symbology: Symbology, data: &str) -> Result<Encoded> {
+    match symbology {
+        Symbology::Ean13 => {
+            let digits = with_check_digit(data, 13, "EAN-13")?;
+            Ok(Encoded {
+                modules: ean(&digits[1..], EAN13_PARITY[digits[0] as usize]),
+                quiet_zone: 11,
+                text: format!("{} {} {}", digits[0], digit_string(&digits[1..7]), digit_string(&digits[7..])),
+            })
+        },
+        Symbology::UpcA => {
+            // UPC-A is EAN-13 with a leading zero, which selects all-odd
+            // parity for the left half.
+            let digits = with_check_digit(data, 12, "UPC-A")?;
+            Ok(Encoded {
+                modules: ean(&digits, EAN13_PARITY[0]),
+                quiet_zone: 9,
+                text: format!(
+                    "{} {} {} {}",
+                    digits[0],
+                    digit_string(&digits[1..6]),
+                    digit_string(&digits[6..11]),
+                    digits[11],
+                ),
+            })
+        },
+        Symbology::Ean8 => {
+            let digits = with_check_digit(data, 8, "EAN-8")?;
+            Ok(Encoded {
+                modules: ean(&digits, EAN13_PARITY[0]),
+                quiet_zone: 7,
+                text: format!("{} {}", digit_string(&digits[..4]), digit_string(&digits[4..])),
+            })
+        },
+        Symbology::Code128 => Ok(Encoded {
+            modules: code128(data)?,
+            quiet_zone: 10,
+            text: data.to_string(),
+        }),
+        Symbology::Code39 => Ok(Encoded {
+            modules: code39(data)?,
+            quiet_zone: 10,
+            text: format!("*{}*", data),
+        }),
+    }
+}
+
+/// Append `count` modules of the given color.
+fn push_run(modules: &mut Vec<bool>, dark: bool, count: u8) {
+    modules.extend(std::iter::repeat_n(dark, count as usize));
+}
+
+/// Append a pattern given as the widths of alternating bars and spaces,
+/// starting with a bar.
+fn push_widths(modules: &mut Vec<bool>, widths: &[u8]) {
+    for (i, &width) in widths.iter().enumerate() {
+        push_run(modules, i % 2 == 0, width);
+    }
+}
+
+/// Append a pattern given as a string of ones (dark) and zeros (light).
+fn push_bits(modules: &mut Vec<bool>, bits: &str) {
+    modules.extend(bits.chars().map(|c| c == '1'));
+}
+
+fn digit_string(digits: &[u8]) -> String {
+    digits.iter().map(|d| char::from(b'0' + d)).collect()
+}
+
+// EAN/UPC
+
+/// The left-hand, odd parity ("L") patterns for the digits 0-9. The right-hand
+/// ("R") patterns are their inverse, the even parity ("G") patterns are the R
+/// patterns reversed.
+const EAN_L: [&str; 10] = [
+    "0001101", "0011001", "0010011", "0111101", "0100011",
+    "0110001", "0101111", "0111011", "0110111", "0001011",
+];
+
+/// For EAN-13, the first digit isn't encoded directly. Instead, it selects
+/// which of the digits in the left half use even parity (`true`).
+const EAN13_PARITY: [[bool; 6]; 10] = [
+    [false, false, false, false, false, false],
+    [false, false, true, false, true, true],
+    [false, false, true, true, false, true],
+    [false, false, true, true, true, false],
+    [false, true, false, false, true, true],
+    [false, true, true, false, false, true],
+    [false, true, true, true, false, false],
+    [false, true, false, true, false, true],
+    [false, true, false, true, true, false],
+    [false, true, true, false, true, false],
+];
+
+/// Parse `data` as the digits of an EAN/UPC code that's `length` digits long
+/// including the check digit. If the check digit is missing, it's computed.
+fn with_check_digit(data: &str, length: usize, name: &str) -> Result<Vec<u8>> {
+    if !data.chars().all(|c| c.is_ascii_digit()) {
+        bail!("{} can only encode digits, got {:?}", name, data);
+    }
+    let mut digits: Vec<u8> = data.bytes().map(|b| b - b'0').collect();
+    if digits.len() != length && digits.len() != length - 1 {
+        bail!("{} needs {} or {} digits, got {:?}", name, length - 1, length, data);
+    }
+
+    let check = ean_check_digit(&digits[..length - 1]);
+    if digits.len() == length {
+        if digits[length - 1] != check {
+            bail!("Invalid check digit in {} {:?}, expected {}", name, data, check);
+        }
+    } else {
+        digits.push(check);
+    }
+    Ok(digits)
+}
+
+/// The check digit for the given digits: weighting them 3, 1, 3, ... from the
+/// right, it's whatever brings the sum to the next multiple of ten.
+fn ean_check_digit(digits: &[u8]) -> u8 {
+    let sum: u32 = digits.iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| d as u32 * if i % 2 == 0 { 3 } else { 1 })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+/// Encode the digits of an EAN/UPC code. Half of the digits go to the left of
+/// the center guard, the other half to the right. `parity` selects even parity
+/// for the digits in the left half.
+fn ean(digits: &[u8], parity: [bool; 6]) -> Vec<bool> {
+    let (left, right) = digits.split_at(digits.len() / 2);
+    let mut modules = Vec::new();
+    push_bits(&mut modules, "101");
+    for (i, &digit) in left.iter().enumerate() {
+        let pattern = EAN_L[digit as usize];
+        if parity[i] {
+            modules.extend(pattern.chars().rev().map(|c| c == '0'));
+        } else {
+            push_bits(&mut modules, pattern);
+        }
+    }
+    push_bits(&mut modules, "01010");
+    for &digit in right {
+        modules.extend(EAN_L[digit as usize].chars().map(|c| c == '0'));
+    }
+    push_bits(&mut modules, "101");
+    modules
+}
+
+// Code 128
+
+/// The widths of the bars and spaces for the symbol values 0-105.
+const CODE128_PATTERNS: [[u8; 6]; 106] = [
+    [2, 1, 2, 2, 2, 2], [2, 2, 2, 1, 2, 2], [2, 2, 2, 2, 2, 1], [1, 2, 1, 2, 2, 3],
+    [1, 2, 1, 3, 2, 2], [1, 3, 1, 2, 2, 2], [1, 2, 2, 2, 1, 3], [1, 2, 2, 3, 1, 2],
+    [1, 3, 2, 2, 1, 2], [2, 2, 1, 2, 1, 3], [2, 2, 1, 3, 1, 2], [2, 3, 1, 2, 1, 2],
+    [1, 1, 2, 2, 3, 2], [1, 2, 2, 1, 3, 2], [1, 2, 2, 2, 3, 1], [1, 1, 3, 2, 2, 2],
+    [1, 2, 3, 1, 2, 2], [1, 2, 3, 2, 2, 1], [2, 2, 3, 2, 1, 1], [2, 2, 1, 1, 3, 2],
+    [2, 2, 1, 2, 3, 1], [2, 1, 3, 2, 1, 2], [2, 2, 3, 1, 1, 2], [3, 1, 2, 1, 3, 1],
+    [3, 1, 1, 2, 2, 2], [3, 2, 1, 1, 2, 2], [3, 2, 1, 2, 2, 1], [3, 1, 2, 2, 1, 2],
+    [3, 2, 2, 1, 1, 2], [3, 2, 2, 2, 1, 1], [2, 1, 2, 1, 2, 3], [2, 1, 2, 3, 2, 1],
+    [2, 3, 2, 1, 2, 1], [1, 1, 1, 3, 2, 3], [1, 3, 1, 1, 2, 3], [1, 3, 1, 3, 2, 1],
+    [1, 1, 2, 3, 1, 3], [1, 3, 2, 1, 1, 3], [1, 3, 2, 3, 1, 1], [2, 1, 1, 3, 1, 3],
+    [2, 3, 1, 1, 1, 3], [2, 3, 1, 3, 1, 1], [1, 1, 2, 1, 3, 3], [1, 1, 2, 3, 3, 1],
+    [1, 3, 2, 1, 3, 1], [1, 1, 3, 1, 2, 3], [1, 1, 3, 3, 2, 1], [1, 3, 3, 1, 2, 1],
+    [3, 1, 3, 1, 2, 1], [2, 1, 1, 3, 3, 1], [2, 3, 1, 1, 3, 1], [2, 1, 3, 1, 1, 3],
+    [2, 1, 3, 3, 1, 1], [2, 1, 3, 1, 3, 1], [3, 1, 1, 1, 2, 3], [3, 1, 1, 3, 2, 1],
+    [3, 3, 1, 1, 2, 1], [3, 1, 2, 1, 1, 3], [3, 1, 2, 3, 1, 1], [3, 3, 2, 1, 1, 1],
+    [3, 1, 4, 1, 1, 1], [2, 2, 1, 4, 1, 1], [4, 3, 1, 1, 1, 1], [1, 1, 1, 2, 2, 4],
+    [1, 1, 1, 4, 2, 2], [1, 2, 1, 1, 2, 4], [1, 2, 1, 4, 2, 1], [1, 4, 1, 1, 2, 2],
+    [1, 4, 1, 2, 2, 1], [1, 1, 2, 2, 1, 4], [1, 1, 2, 4, 1, 2], [1, 2, 2, 1, 1, 4],
+    [1, 2, 2, 4, 1, 1], [1, 4, 2, 1, 1, 2], [1, 4, 2, 2, 1, 1], [2, 4, 1, 2, 1, 1],
+    [2, 2, 1, 1, 1, 4], [4, 1, 3, 1, 1, 1], [2, 4, 1, 1, 1, 2], [1, 3, 4, 1, 1, 1],
+    [1, 1, 1, 2, 4, 2], [1, 2, 1, 1, 4, 2], [1, 2, 1, 2, 4, 1], [1, 1, 4, 2, 1, 2],
+    [1, 2, 4, 1, 1, 2], [1, 2, 4, 2, 1, 1], [4, 1, 1, 2, 1, 2], [4, 2, 1, 1, 1, 2],
+    [4, 2, 1, 2, 1, 1], [2, 1, 2, 1, 4, 1], [2, 1, 4, 1, 2, 1], [4, 1, 2, 1, 2, 1],
+    [1, 1, 1, 1, 4, 3], [1, 1, 1, 3, 4, 1], [1, 3, 1, 1, 4, 1], [1, 1, 4, 1, 1, 3],
+    [1, 1, 4, 3, 1, 1], [4, 1, 1, 1, 1, 3], [4, 1, 1, 3, 1, 1], [1, 1, 3, 1, 4, 1],
+    [1, 1, 4, 1, 3, 1], [3, 1, 1, 1, 4, 1], [4, 1, 1, 1, 3, 1], [2, 1, 1, 4, 1, 2],
+    [2, 1, 1, 2, 1, 4], [2, 1, 1, 2, 3, 2],
+];
+
+/// The stop pattern, which has an extra termination bar.
+const CODE128_STOP: [u8; 7] = [2, 3, 3, 1, 1, 1, 2];
+
+const CODE128_START_B: u8 = 104;
+const CODE128_START_C: u8 = 105;
+const CODE128_SWITCH_TO_B: u8 = 100;
+const CODE128_SWITCH_TO_C: u8 = 99;
+
+/// The number of ASCII digits at the start of `data`.
+fn leading_digits(data: &[u8]) -> usize {
+    data.iter().take_while(|b| b.is_ascii_digit()).count()
+}
+
+/// Encode `data` using code set B for text and code set C for runs of digits,
+/// which packs two digits into each symbol.
+fn code128(data: &str) -> Result<Vec<bool>> {
+    if let Some(c) = data.chars().find(|c| !(' '..='~').contains(c)) {
+        bail!("Code 128 can only encode printable ASCII, got {:?} in {:?}", c, data);
+    }
+
+    let bytes = data.as_bytes();
+    // Switching to code set C costs a symbol, so it's only worth it for at
+    // least four digits. At the very start, it's free.
+    let starts_with_digits = leading_digits(bytes) >= 4 ||
+        (!bytes.is_empty() && leading_digits(bytes) == bytes.len() && bytes.len().is_multiple_of(2));
+    let mut in_c = starts_with_digits;
+    let mut symbols = vec![if in_c { CODE128_START_C } else { CODE128_START_B }];
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let digits = leading_digits(&bytes[i..]);
+        if in_c {
+            if digits >= 2 {
+                symbols.push((bytes[i] - b'0') * 10 + bytes[i + 1] - b'0');
+                i += 2;
+                continue;
+            }
+            symbols.push(CODE128_SWITCH_TO_B);
+            in_c = false;
+        } else if digits >= 4 && digits.is_multiple_of(2) {
+            symbols.push(CODE128_SWITCH_TO_C);
+            in_c = true;
+            continue;
+        }
+        symbols.push(bytes[i] - b' ');
+        i += 1;
+    }
+
+    let checksum = symbols.iter()
+        .enumerate()
+        // The start symbol and the first data symbol both have a weight of
+        // one.
+        .map(|(i, &s)| cmp::max(1, i) * s as usize)
+        .sum::<usize>() % 103;
+    symbols.push(checksum as u8);
+
+    let mut modules = Vec::new();
+    for symbol in symbols {
+        push_widths(&mut modules, &CODE128_PATTERNS[symbol as usize]);
+    }
+    push_widths(&mut modules, &CODE128_STOP);
+    Ok(modules)
+}
+
+// Code 39
+
+/// The characters Code 39 can encode, in the order of [CODE39_PATTERNS].
+const CODE39_CHARS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-. $/+%*";
+
+/// Code 39 patterns: nine bits for five bars and four interleaved spaces, from
+/// left to right, starting at the most significant bit. A set bit is a wide
+/// element. Every character has exactly three wide elements.
+const CODE39_PATTERNS: [u16; 44] = [
+    0x034, 0x121, 0x061, 0x160, 0x031, 0x130, 0x070, 0x025, 0x124, 0x064,
+    0x109, 0x049, 0x148, 0x019, 0x118, 0x058, 0x00D, 0x10C, 0x04C, 0x01C,
+    0x103, 0x043, 0x142, 0x013, 0x112, 0x052, 0x007, 0x106, 0x046, 0x016,
+    0x181, 0x0C1, 0x1C0, 0x091, 0x190, 0x0D0, 0x085, 0x184, 0x0C4, 0x0A8,
+    0x0A2, 0x08A, 0x02A, 0x094,
+];
+
+/// How many modules a wide element takes up. Narrow ones take up one.
+const CODE39_WIDE: u8 = 3;
+
+/// Encode `data` between the `*` start and stop characters, with a narrow space
+/// between characters.
+fn code39(data: &str) -> Result<Vec<bool>> {
+    if let Some(c) = data.chars().find(|&c| c == '*' || !CODE39_CHARS.contains(c)) {
+        bail!("Code 39 can't encode {:?} in {:?}", c, data);
+    }
+
+    let mut modules = Vec::new();
+    for (i, c) in format!("*{}*", data).chars().enumerate() {
+        let index = CODE39_CHARS.find(c).unwrap();
+        if i > 0 {
+            push_run(&mut modules, false, 1);
+        }
+        let pattern = CODE39_PATTERNS[index];
+        let widths: Vec<u8> = (0..9)
+            .rev()
+            .map(|bit| if pattern & (1 << bit) != 0 { CODE39_WIDE } else { 1 })
+            .collect();
+        push_widths(&mut modules, &widths);
+    }
+    Ok(modules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(modules: &[bool]) -> String {
+        modules.iter().map(|&m| if m { '1' } else { '0' }).collect()
+    }
+
+    #[test]
+    fn test_ean13_computes_and_verifies_the_check_digit() {
+        let computed = encode(Symbology::Ean13, "400638133393").unwrap();
+        let given = encode(Symbology::Ean13, "4006381333931").unwrap();
+        assert_eq!(computed, given);
+        assert_eq!("4 006381 333931", given.text);
+        assert!(encode(Symbology::Ean13, "4006381333932").is_err());
+        assert!(encode(Symbology::Ean13, "40063813339").is_err());
+        assert!(encode(Symbology::Ean13, "40063813339a").is_err());
+    }
+
+    #[test]
+    fn test_ean13_modules() {
+        let code = encode(Symbology::Ean13, "5901234123457").unwrap();
+        assert_eq!(95, code.modules.len());
+        let bits = bits(&code.modules);
+        // The leading 5 selects LGGLLG for the left half, so the first digit
+        // (9) uses L and the second one (0) uses G.
+        assert_eq!("101", &bits[..3]);
+        assert_eq!("0001011", &bits[3..10]);
+        assert_eq!("0100111", &bits[10..17]);
+        assert_eq!("01010", &bits[45..50]);
+        // The right half uses R, the inverse of L: 1 is 1100110.
+        assert_eq!("1100110", &bits[50..57]);
+        assert_eq!("101", &bits[92..]);
+    }
+
+    #[test]
+    fn test_upc_a_matches_ean13_with_leading_zero() {
+        let upc = encode(Symbology::UpcA, "03600029145").unwrap();
+        let ean = encode(Symbology::Ean13, "0036000291452").unwrap();
+        assert_eq!(ean.modules, upc.modules);
+        assert_eq!("0 36000 29145 2", upc.text);
+    }
+
+    #[test]
+    fn test_ean8() {
+        let code = encode(Symbology::Ean8, "9638507").unwrap();
+        assert_eq!(67, code.modules.len());
+        assert_eq!("9638 5074", code.text);
+    }
+
+    #[test]
+    fn test_code128_patterns_are_well_formed() {
+        for pattern in CODE128_PATTERNS {
+            assert_eq!(11, pattern.iter().map(|&w| w as u32).sum::<u32>());
+            assert_eq!(0, (pattern[0] + pattern[2] + pattern[4]) % 2);
+        }
+    }
+
+    #[test]
+    fn test_code128_uses_code_set_c_for_digits() {
+        // Start C, 12, 34, checksum, stop: 4 symbols and the 13 module stop.
+        let digits = encode(Symbology::Code128, "1234").unwrap();
+        assert_eq!(4 * 11 + 13, digits.modules.len());
+        // Start B, 4 characters, checksum, stop.
+        let text = encode(Symbology::Code128, "ab12").unwrap();
+        assert_eq!(6 * 11 + 13, text.modules.len());
+        // Start B, "A", switch to C, 12, 34, checksum, stop.
+        let mixed = encode(Symbology::Code128, "A1234").unwrap();
+        assert_eq!(6 * 11 + 13, mixed.modules.len());
+        assert!(encode(Symbology::Code128, "Grüße").is_err());
+    }
+
+    #[test]
+    fn test_code128_checksum() {
+        // Start B (104) + 1 * "P" (48) + 2 * "J" (42) = 236, 236 % 103 = 30.
+        let code = encode(Symbology::Code128, "PJ").unwrap();
+        let checksum = &code.modules[33..44];
+        let mut expected = Vec::new();
+        push_widths(&mut expected, &CODE128_PATTERNS[30]);
+        assert_eq!(expected, checksum);
+    }
+
+    #[test]
+    fn test_code39() {
+        let code = encode(Symbology::Code39, "A1").unwrap();
+        // Four characters of 15 modules each, plus three gaps.
+        assert_eq!(4 * 15 + 3, code.modules.len());
+        assert_eq!("*A1*", code.text);
+        // The start character: narrow bar, wide space, narrow bar, ...
+        assert_eq!("1000101110111010", bits(&code.modules[..16]));
+        assert!(encode(Symbology::Code39, "a").is_err());
+        assert!(encode(Symbology::Code39, "A*B").is_err());
+    }
+}
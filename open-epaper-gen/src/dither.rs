@@ -0,0 +1,213 @@
+//! Quantization of the finished image to the colors of a panel.
+//!
+//! Once a module is done drawing, the [Surface](crate::draw::Surface) contains
+//! an RGB image with arbitrary colors: anti-aliased text, photos, logos, and so
+//! on. The panel can only display the handful of colors in its [Palette], so
+//! before we save or upload the image, every pixel is mapped to one of those.
+//!
+//! How that mapping happens is determined by [Dithering]. Plain thresholding
+//! (snapping to the closest color) keeps text and lines crisp, but turns photos
+//! into blobs. The error diffusion methods ([Dithering::FloydSteinberg] and
+//! [Dithering::Atkinson]) and ordered dithering ([Dithering::Ordered]) trade
+//! some crispness for the ability to show gradients.
+
+use image::RgbImage;
+use serde::Deserialize;
+use clap::ValueEnum;
+
+use crate::draw::Palette;
+
+/// The dithering algorithm used to quantize an image to a [Palette].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Dithering {
+    /// Snap every pixel to the closest palette color. Best for text and line
+    /// art.
+    Threshold,
+    /// Floyd–Steinberg error diffusion. Good all-round choice for photos.
+    FloydSteinberg,
+    /// Atkinson error diffusion. Only diffuses part of the error, which results
+    /// in more contrast and less noise than Floyd–Steinberg, at the cost of
+    /// losing detail in very dark and very light areas.
+    Atkinson,
+    /// Ordered dithering using a 4x4 Bayer matrix. Produces a regular
+    /// cross-hatch pattern and never smears errors across edges.
+    Ordered,
+}
+
+/// Error diffusion kernel for Floyd–Steinberg: (dx, dy, weight), with the
+/// weights being sixteenths.
+const FLOYD_STEINBERG: [(i32, i32, f32); 4] = [
+    (1, 0, 7.0 / 16.0),
+    (-1, 1, 3.0 / 16.0),
+    (0, 1, 5.0 / 16.0),
+    (1, 1, 1.0 / 16.0),
+];
+
+/// Error diffusion kernel for Atkinson. Only 6/8 of the error are diffused.
+const ATKINSON: [(i32, i32, f32); 6] = [
+    (1, 0, 1.0 / 8.0),
+    (2, 0, 1.0 / 8.0),
+    (-1, 1, 1.0 / 8.0),
+    (0, 1, 1.0 / 8.0),
+    (1, 1, 1.0 / 8.0),
+    (0, 2, 1.0 / 8.0),
+];
+
+/// 4x4 Bayer threshold matrix for ordered dithering.
+const BAYER_4X4: [[u8; 4]; 4] = [
+    [0, 8, 2, 10],
+    [12, 4, 14, 6],
+    [3, 11, 1, 9],
+    [15, 7, 13, 5],
+];
+
+/// Quantize `img` in place so that it only contains colors from `palette`.
+pub fn dither(img: &mut RgbImage, palette: Palette, dithering: Dithering) {
+    match dithering {
+        Dithering::Threshold => threshold(img, palette),
+        Dithering::FloydSteinberg => diffuse_error(img, palette, &FLOYD_STEINBERG),
+        Dithering::Atkinson => diffuse_error(img, palette, &ATKINSON),
+        Dithering::Ordered => ordered(img, palette),
+    }
+}
+
+fn threshold(img: &mut RgbImage, palette: Palette) {
+    for pixel in img.pixels_mut() {
+        *pixel = palette.closest(*pixel);
+    }
+}
+
+fn diffuse_error(img: &mut RgbImage, palette: Palette, kernel: &[(i32, i32, f32)]) {
+    let width = img.width() as i32;
+    let height = img.height() as i32;
+
+    // Keep the accumulated error per channel in a separate buffer. It can push
+    // values outside of 0-255, which we don't want to clamp until we've picked
+    // the palette color.
+    let mut buffer: Vec<[f32; 3]> = img.pixels()
+        .map(|p| [p.0[0] as f32, p.0[1] as f32, p.0[2] as f32])
+        .collect();
+
+    for y in 0..height {
+        for x in 0..width {
+            let index = (y * width + x) as usize;
+            let old = buffer[index];
+            let clamped = image::Rgb(old.map(|c| c.round().clamp(0.0, 255.0) as u8));
+            let new = palette.closest(clamped);
+            img.put_pixel(x as u32, y as u32, new);
+
+            let error = [
+                old[0] - new.0[0] as f32,
+                old[1] - new.0[1] as f32,
+                old[2] - new.0[2] as f32,
+            ];
+            for &(dx, dy, weight) in kernel {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || nx >= width || ny >= height {
+                    continue;
+                }
+                let neighbor = &mut buffer[(ny * width + nx) as usize];
+                for c in 0..3 {
+                    neighbor[c] += error[c] * weight;
+                }
+            }
+        }
+    }
+}
+
+fn ordered(img: &mut RgbImage, palette: Palette) {
+    // Spread the threshold offsets over the distance between two neighboring
+    // gray levels of the palette. For a black and white palette, that's the
+    // full range. Accent colors aren't gray levels, so black/white/red panels
+    // get the full range as well.
+    let grays = palette.colors()
+        .iter()
+        .filter(|c| c.0[0] == c.0[1] && c.0[1] == c.0[2])
+        .count();
+    let spread = 255.0 / (grays - 1) as f32;
+
+    for (x, y, pixel) in img.enumerate_pixels_mut() {
+        let threshold = BAYER_4X4[(y % 4) as usize][(x % 4) as usize] as f32;
+        let offset = ((threshold + 0.5) / 16.0 - 0.5) * spread;
+        let shifted = image::Rgb(pixel.0.map(|c| (c as f32 + offset).round().clamp(0.0, 255.0) as u8));
+        *pixel = palette.closest(shifted);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray_image(width: u32, height: u32, level: u8) -> RgbImage {
+        RgbImage::from_pixel(width, height, image::Rgb([level, level, level]))
+    }
+
+    fn black_ratio(img: &RgbImage) -> f32 {
+        let black = img.pixels().filter(|p| p.0 == [0, 0, 0]).count();
+        black as f32 / (img.width() * img.height()) as f32
+    }
+
+    #[test]
+    fn test_all_methods_only_output_palette_colors() {
+        for dithering in Dithering::value_variants() {
+            let mut img = RgbImage::from_fn(32, 32, |x, y| {
+                image::Rgb([(x * 8) as u8, (y * 8) as u8, 128])
+            });
+            dither(&mut img, Palette::BlackWhiteRed, *dithering);
+            let colors = Palette::BlackWhiteRed.colors();
+            assert!(img.pixels().all(|p| colors.contains(p)), "{:?}", dithering);
+        }
+    }
+
+    #[test]
+    fn test_threshold_turns_mid_gray_into_a_solid_color() {
+        let mut img = gray_image(16, 16, 100);
+        dither(&mut img, Palette::BlackWhite, Dithering::Threshold);
+        assert_eq!(1.0, black_ratio(&img));
+    }
+
+    #[test]
+    fn test_error_diffusion_approximates_gray_level() {
+        let mut img = gray_image(32, 32, 128);
+        dither(&mut img, Palette::BlackWhite, Dithering::FloydSteinberg);
+        let ratio = black_ratio(&img);
+        assert!(ratio > 0.4 && ratio < 0.6, "{}", ratio);
+    }
+
+    #[test]
+    fn test_atkinson_mixes_black_and_white() {
+        let mut img = gray_image(32, 32, 128);
+        dither(&mut img, Palette::BlackWhite, Dithering::Atkinson);
+        let ratio = black_ratio(&img);
+        assert!(ratio > 0.3 && ratio < 0.7, "{}", ratio);
+    }
+
+    #[test]
+    fn test_ordered_dithering_renders_half_of_a_bayer_tile_for_mid_gray() {
+        let mut img = gray_image(4, 4, 128);
+        dither(&mut img, Palette::BlackWhite, Dithering::Ordered);
+        assert_eq!(0.5, black_ratio(&img));
+    }
+
+    #[test]
+    fn test_ordered_dithering_keeps_dark_grays_on_accent_palettes() {
+        for palette in [Palette::BlackWhiteRed, Palette::BlackWhiteYellow] {
+            let mut img = gray_image(4, 4, 40);
+            dither(&mut img, palette, Dithering::Ordered);
+            let ratio = black_ratio(&img);
+            assert!(ratio > 0.5 && ratio < 1.0, "{:?}: {}", palette, ratio);
+        }
+    }
+
+    #[test]
+    fn test_pure_palette_colors_are_left_alone() {
+        for dithering in Dithering::value_variants() {
+            let mut img = gray_image(8, 8, 255);
+            img.put_pixel(3, 3, image::Rgb([0, 0, 0]));
+            let expected = img.clone();
+            dither(&mut img, Palette::BlackWhite, *dithering);
+            assert_eq!(expected, img, "{:?}", dithering);
+        }
+    }
+}
@@ -2,9 +2,12 @@
 //! well as a basic layout system roughly based on what SwiftUI's doing.
 //!
 //! Everything in this module is drawn on a [Surface]. The surface itself is
-//! just a very thin wrapper around an [image::RgbImage] -- in fact, all of the
-//! other drawing primitives in here access `surface.img`, which directly
-//! exposes the [image::RgbImage].
+//! just a very thin wrapper around an [image::RgbImage], which it exposes as
+//! `surface.img`. Views draw through [Surface::set_pixel] and
+//! [Surface::blend_pixel], though, which discard pixels outside of the
+//! surface's current clip rectangle (see [Surface::push_clip]). The stacks clip
+//! each child to the space they allocated for it, so a view that draws more
+//! than it said it would can't paint over its neighbors or crash the app.
 //!
 //! Everything else -- all the drawing primitives and layout helpers --
 //! implements the [View] trait. The most important thing about the [View] is
@@ -13,8 +16,20 @@
 //! method to use. Most of the time, you'll want to only call that method on a
 //! top-level layout helper, though.
 //!
-//! As of now, the only two drawing primitives supported are [Text] and [Image].
-//! Both are fairly straightforward to use, see their respective documentation.
+//! The main drawing primitives are [Text] and [Image]. Both are fairly
+//! straightforward to use, see their respective documentation. For text that
+//! mixes fonts, sizes, or colors, use [RichText]. Vector logos and icons can be
+//! drawn at any size using [SvgImage], and links or WiFi credentials as a
+//! [QrCode]. Product codes are drawn using [Barcode]. Numeric series can be
+//! shown as a [Sparkline], a [LineChart], or a [BarChart], and single
+//! percentages as a [ProgressBar], a [Gauge], or a [LevelIndicator]. For boxes,
+//! separators, and bullet dots, there are shapes: [Rectangle],
+//! [RoundedRectangle], [Circle], [Line], and [Divider]. Their outline and fill
+//! are configured using a [ShapeStyle].
+//!
+//! Any view can be wrapped in a [Background], a [Border], or an [Invert] to
+//! paint behind it, frame it, or invert it. [Rotate] turns a view sideways or
+//! upside down.
 //!
 //! ## Layout
 //!
@@ -42,17 +57,21 @@
 //! let mut screen = HStack::new();
 //!
 //! let mut berlin = VStack::new();
-//! berlin.views.push(Box::new(Text::new(String::from("Berlin"), 13.0, 0)));
-//! berlin.views.push(Box::new(Text::new(String::from("14:21"), 13.0, 0)));
+//! berlin.views.push(Box::new(Text::new(String::from("Berlin"), 13.0, "Roboto")));
+//! berlin.views.push(Box::new(Text::new(String::from("14:21"), 13.0, "Roboto")));
 //!
 //! let mut london = VStack::new();
-//! london.views.push(Box::new(Text::new(String::from("London"), 13.0, 0)));
-//! london.views.push(Box::new(Text::new(String::from("13:21"), 13.0, 0)));
+//! london.views.push(Box::new(Text::new(String::from("London"), 13.0, "Roboto")));
+//! london.views.push(Box::new(Text::new(String::from("13:21"), 13.0, "Roboto")));
 //!
 //! screen.views.push(berlin);
 //! screen.views.push(london);
 //! ```
 //!
+//! To layer views on top of each other instead -- say, a timestamp in the
+//! corner of a picture -- use a [ZStack]. For tabular layouts where columns
+//! need to line up, use a [Grid].
+//!
 //! Let's say our screen is a bit wider than in the example above, and we want
 //! to push Berlin to the left of the screen and London to the right of the
 //! screen, instead of having it all on the left hand side of the screen. We can
@@ -77,28 +96,47 @@
 //! let mut screen = HStack::new();
 //!
 //! let mut berlin = VStack::new();
-//! berlin.views.push(Box::new(Text::new(String::from("Berlin"), 13.0, 0)));
-//! berlin.views.push(Box::new(Text::new(String::from("14:21"), 13.0, 0)));
+//! berlin.views.push(Box::new(Text::new(String::from("Berlin"), 13.0, "Roboto")));
+//! berlin.views.push(Box::new(Text::new(String::from("14:21"), 13.0, "Roboto")));
 //! berlin.padding(Edge::Left, 10);
 //!
 //! let mut london = VStack::new();
-//! london.views.push(Box::new(Text::new(String::from("London"), 13.0, 0)));
-//! london.views.push(Box::new(Text::new(String::from("13:21"), 13.0, 0)));
+//! london.views.push(Box::new(Text::new(String::from("London"), 13.0, "Roboto")));
+//! london.views.push(Box::new(Text::new(String::from("13:21"), 13.0, "Roboto")));
 //! london.padding(Edge::Right, 10);
 //!
 //! screen.views.push(berlin);
 //! screen.views.push(Box::new(Spacer::horizontal()));
 //! screen.views.push(london);
 //! ```
-
-use fontdue::layout::{CoordinateSystem, Layout, TextStyle};
-use fontdue;
-use image::{ImageBuffer, RgbImage, ImageFormat};
+//!
+//! To find out whether a layout fits before drawing it, call [View::layout] on
+//! the top-level view. If something is too large, it returns an [Overflow]
+//! naming the view and how many pixels too wide and too tall it is, so the
+//! module can make room, e.g. by dropping a view or shrinking a font.
+//!
+//! When a layout comes out wrong, set [Surface::debug_layout] and draw the
+//! top-level view using [Surface::draw_view]. The surface then records where
+//! each view was drawn and with which bounds in its [Surface::layout_tree],
+//! and [Surface::layout_preview] outlines those frames on a copy of the image.
+
+use fontdue::layout::{CoordinateSystem, GlyphPosition, Layout, TextStyle};
+use image::{ImageBuffer, RgbImage, RgbaImage, ImageFormat, Pixel};
+use image::imageops::{self, FilterType};
+use std::cell::{Ref, RefCell};
 use std::io::{BufReader, Read, Seek};
+use std::path::{Path, PathBuf};
 use std::cmp;
 use std::ops::{Add, Sub};
+use std::fmt;
 use std::fs;
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use clap::ValueEnum;
+
+use crate::dither::{self, Dithering};
+use crate::barcode;
+pub use crate::barcode::Symbology;
 
 /// A surface to draw on. This is really just a wrapper for [image::RgbImage],
 /// which you can access using the [img] field.
@@ -107,24 +145,43 @@ pub struct Surface {
     /// The underlying [image::RgbImage]. If you're implementing a [View], then
     /// you'll probably want to access this.
     pub img: RgbImage,
+    /// How [Text] views render their glyphs unless they specify a rendering
+    /// mode themselves. Defaults to [TextRendering::Threshold].
+    pub text_rendering: TextRendering,
+    /// The colors the target panel can actually display. Views draw using
+    /// [Color]s, which are resolved against this palette. Defaults to
+    /// [Palette::BlackWhite].
+    pub palette: Palette,
+    /// Fonts every [Text] falls back to for characters that neither its font
+    /// nor its own fallback fonts have, see [Text::fallback_fonts]. Empty by
+    /// default.
+    pub fallback_fonts: Vec<String>,
+    /// The clip rectangles pushed by [push_clip], each already intersected
+    /// with the ones before it.
+    clips: Vec<Rect>,
+    /// Whether to record where views are drawn, see [draw_view]. Disabled by
+    /// default.
+    pub debug_layout: bool,
+    /// The views drawn while [debug_layout] was set, see [layout_tree].
+    layout_tree: Vec<LayoutNode>,
+    /// The views that are currently being drawn, outermost first.
+    open_layout_nodes: Vec<LayoutNode>,
 }
 
 impl Surface {
     /// Create a new surface with the given dimensions.
+    ///
+    /// The fonts in `resources` are registered as "Roboto", "RobotoMono", and
+    /// "PlayfairDisplay". Only [DEFAULT_FONT] is loaded right away, the others
+    /// are loaded the first time they're used.
     pub fn new(x_size: u32, y_size: u32) -> Result<Surface> {
-        let roboto_data = fs::read(Surface::font_path("Roboto-Regular.ttf")?)
-            .with_context(|| format!("Can't read Roboto-Regular.ttf"))?;
-        let roboto = fontdue::Font::from_bytes(roboto_data, fontdue::FontSettings::default())
-            .map_err(|str| anyhow!(str))?;
-
-        let playfair_data = fs::read(Surface::font_path("PlayfairDisplay-Regular.ttf")?)
-            .with_context(|| format!("Can't read PlayfairDisplay-Regular.ttf"))?;
-        let playfair = fontdue::Font::from_bytes(playfair_data, fontdue::FontSettings::default())
-            .map_err(|str| anyhow!(str))?;
-
         let mut font_cache = FontCache::new();
-        font_cache.add(Font::Roboto, roboto);
-        font_cache.add(Font::PlayfairDisplay, playfair);
+        font_cache.register(DEFAULT_FONT, FontSource::File(Surface::font_path("Roboto-Regular.ttf")?));
+        font_cache.register("RobotoMono", FontSource::File(Surface::font_path("RobotoMono-Regular.ttf")?));
+        font_cache.register("PlayfairDisplay", FontSource::File(Surface::font_path("PlayfairDisplay-Regular.ttf")?));
+        // Text falls back to the default font if its own font isn't usable, so
+        // that one has to work.
+        font_cache.load(DEFAULT_FONT)?;
 
         let mut img: RgbImage = ImageBuffer::new(x_size, y_size);
         let white = image::Rgb([255, 255, 255]);
@@ -136,7 +193,14 @@ impl Surface {
 
         Ok(Surface {
             fonts: font_cache,
-            img: img,
+            img,
+            text_rendering: TextRendering::Threshold,
+            palette: Palette::BlackWhite,
+            fallback_fonts: Vec::new(),
+            clips: Vec::new(),
+            debug_layout: false,
+            layout_tree: Vec::new(),
+            open_layout_nodes: Vec::new(),
         })
     }
 
@@ -144,66 +208,475 @@ impl Surface {
         Bounds::new(self.img.width(), self.img.height())
     }
 
-    fn font_path(font: &str) -> Result<String> {
+    /// Resolve a [Color] to the actual RGB value for this surface's palette.
+    pub fn rgb(&self, color: Color) -> image::Rgb<u8> {
+        match color {
+            Color::Black => BLACK,
+            Color::White => WHITE,
+            Color::Accent => self.palette.accent(),
+        }
+    }
+
+    /// Map every pixel of the surface to a color in the surface's palette,
+    /// using the given [Dithering] method. Call this once everything has been
+    /// drawn, right before saving or uploading the image, so the access point
+    /// doesn't have to guess how to map our colors onto the panel.
+    pub fn quantize(&mut self, dithering: Dithering) {
+        dither::dither(&mut self.img, self.palette, dithering);
+    }
+
+    /// Turn the finished image from the orientation the modules drew in into
+    /// the orientation of a panel that's mounted rotated clockwise by
+    /// `rotation` (and viewed in a mirror, if `mirror` is set): first mirror
+    /// the image horizontally, then rotate it by `rotation` the other way
+    /// round, so that mounting the panel turns it upright again. For sideways
+    /// rotations, this swaps the surface's width and height. Call this once
+    /// everything has been drawn.
+    pub fn orient(&mut self, rotation: Rotation, mirror: bool) {
+        if mirror {
+            imageops::flip_horizontal_in_place(&mut self.img);
+        }
+        match rotation {
+            Rotation::None => {},
+            Rotation::Clockwise90 => self.img = imageops::rotate270(&self.img),
+            Rotation::Clockwise180 => imageops::rotate180_in_place(&mut self.img),
+            Rotation::Clockwise270 => self.img = imageops::rotate90(&self.img),
+        }
+    }
+
+    /// The area drawing is currently restricted to: the intersection of all
+    /// pushed clip rectangles, or the whole surface if there are none.
+    pub fn clip_rect(&self) -> Rect {
+        self.clips
+            .last()
+            .copied()
+            .unwrap_or(Rect::new(0, 0, self.img.width(), self.img.height()))
+    }
+
+    /// Restrict drawing to `rect` (within the current clip rectangle) until
+    /// the matching call to [pop_clip].
+    pub fn push_clip(&mut self, rect: Rect) {
+        let clip = self.clip_rect().intersection(rect);
+        self.clips.push(clip);
+    }
+
+    /// Undo the last call to [push_clip].
+    pub fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+
+    /// Call `draw` with drawing restricted to `rect`, see [push_clip].
+    pub fn with_clip<T>(&mut self, rect: Rect, draw: impl FnOnce(&mut Surface) -> T) -> T {
+        self.push_clip(rect);
+        let result = draw(self);
+        self.pop_clip();
+        result
+    }
+
+    /// Draw `view` at the given coordinates, recording its frame in the
+    /// [layout_tree] if [debug_layout] is set. Views that contain other views
+    /// draw their children through this, so call it for the top-level view as
+    /// well to get the complete tree.
+    pub fn draw_view(&mut self, view: &dyn View, x: u32, y: u32, bounds: Bounds) {
+        if !self.debug_layout {
+            view.draw(self, x, y, bounds);
+            return;
+        }
+
+        let slot = self.clip_rect();
+        self.open_layout_nodes.push(LayoutNode::new(view, x, y, bounds, slot));
+        view.draw(self, x, y, bounds);
+        let node = self.open_layout_nodes.pop().unwrap();
+        match self.open_layout_nodes.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => self.layout_tree.push(node),
+        }
+    }
+
+    /// The top-level views drawn using [draw_view] while [debug_layout] was
+    /// set, each with the views it contains.
+    pub fn layout_tree(&self) -> &[LayoutNode] {
+        &self.layout_tree
+    }
+
+    /// A copy of the image with the frames in the [layout_tree] outlined: red
+    /// for the frame of each view, blue for the area inside its padding, and
+    /// green for the space allocated to spacers and other views that fill
+    /// their slot (see [View::is_spacer]).
+    pub fn layout_preview(&self) -> RgbImage {
+        let mut preview = self.img.clone();
+        let mut nodes: Vec<&LayoutNode> = self.layout_tree.iter().collect();
+        // Outline parents before their children, so nested frames stay
+        // visible where they share an edge.
+        while let Some(node) = nodes.pop() {
+            let frame = node.frame();
+            if node.spacer {
+                // Spacers usually measure zero across their stack, so show
+                // the space they take up as well.
+                outline_rect(&mut preview, node.slot, DEBUG_SPACER_COLOR);
+            }
+            let inner = frame.inset(node.padding);
+            if inner != frame {
+                outline_rect(&mut preview, inner, DEBUG_PADDING_COLOR);
+            }
+            outline_rect(&mut preview, frame, DEBUG_FRAME_COLOR);
+            nodes.extend(node.children.iter().rev());
+        }
+        preview
+    }
+
+    /// Set the pixel at the given coordinates to `color`. Unlike calling
+    /// `put_pixel` on [img] directly, this silently ignores coordinates that
+    /// are outside of the surface or the current clip rectangle.
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: image::Rgb<u8>) {
+        if self.clip_rect().contains(x, y) {
+            self.img.put_pixel(x, y, color);
+        }
+    }
+
+    /// Blend `color` into the pixel at the given coordinates. `alpha` is the
+    /// coverage of the new color: 0 leaves the pixel untouched, 255 replaces it
+    /// completely. Anything in between mixes the new color with whatever is
+    /// already on the surface. Just like [set_pixel], this ignores coordinates
+    /// outside of the surface or the current clip rectangle.
+    pub fn blend_pixel(&mut self, x: u32, y: u32, color: image::Rgb<u8>, alpha: u8) {
+        if alpha == 0 || !self.clip_rect().contains(x, y) {
+            return;
+        }
+
+        let current = self.img.get_pixel(x, y);
+        let alpha = alpha as u32;
+        let mut blended = [0u8; 3];
+        for (i, channel) in blended.iter_mut().enumerate() {
+            let mixed = color.0[i] as u32 * alpha + current.0[i] as u32 * (255 - alpha);
+            *channel = ((mixed + 127) / 255) as u8;
+        }
+
+        self.img.put_pixel(x, y, image::Rgb(blended));
+    }
+
+    /// Register the font file at `path` under `name`, so [Text] can use it.
+    /// The file isn't read until the font is actually used. Registering a
+    /// name again replaces the previous font.
+    pub fn register_font_file<P: AsRef<Path>>(&mut self, name: &str, path: P) {
+        self.fonts.register(name, FontSource::File(path.as_ref().to_path_buf()));
+    }
+
+    /// Register a font from memory (the contents of a TTF or OTF file) under
+    /// `name`.
+    pub fn register_font_bytes(&mut self, name: &str, data: Vec<u8>) {
+        self.fonts.register(name, FontSource::Bytes(data));
+    }
+
+    /// Register every TTF and OTF file in `dir`, using the file name without
+    /// the extension as the font name: `fonts/Inter-Bold.ttf` becomes
+    /// "Inter-Bold". Returns the number of fonts found.
+    pub fn register_font_dir<P: AsRef<Path>>(&mut self, dir: P) -> Result<usize> {
+        let dir = dir.as_ref();
+        let entries = fs::read_dir(dir)
+            .with_context(|| format!("Can't read font directory {:?}", dir))?;
+
+        let mut count = 0;
+        for entry in entries {
+            let path = entry?.path();
+            let is_font = path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("ttf") || e.eq_ignore_ascii_case("otf"));
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if is_font {
+                let name = name.to_string();
+                self.register_font_file(&name, &path);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Load the font with the given name now instead of on first use. Use this
+    /// to report a missing or broken font as an error, rather than having the
+    /// text fall back to [DEFAULT_FONT].
+    pub fn load_font(&self, name: &str) -> Result<()> {
+        self.fonts.load(name).map(|_| ())
+    }
+
+    fn font_path(font: &str) -> Result<PathBuf> {
         Ok(std::env::current_dir()?
             .join("resources")
-            .join(font)
-            .to_str().ok_or(anyhow!("Can't convert path to string."))?
-            .to_string())
+            .join(font))
     }
 }
 
-/// Internal cache for fonts. Used in [Surface]. This stores the actual fonts
-/// ([fontdue::Font]) and their names ([Font]) in two separate vectors. The
-/// reason for this is that the fontdue API expects a slice of [fontdue::Font]
-/// and an index into that slice for selecting a font. Storing them separately
-/// in here makes it easier to use with the fontdue APIs.
+/// The font every [Text] falls back to if its own font can't be used.
+pub const DEFAULT_FONT: &str = "Roboto";
+
+/// Where to load a registered font from.
+enum FontSource {
+    File(PathBuf),
+    Bytes(Vec<u8>),
+}
+
+enum FontState {
+    /// Registered, but not loaded yet.
+    Unloaded(FontSource),
+    /// Loaded, with the index into [FontCache::fonts].
+    Loaded(usize),
+    /// Loading failed. We don't retry.
+    Failed,
+}
+
+/// Internal cache for fonts. Used in [Surface]. This maps font names to the
+/// actual fonts ([fontdue::Font]). The fontdue API expects a slice of
+/// [fontdue::Font] and an index into that slice for selecting a font, so the
+/// loaded fonts live in a vector of their own that only ever grows.
+///
+/// Fonts are loaded lazily, and views only get to see an immutable [Surface]
+/// while measuring themselves, hence the [RefCell]s.
 struct FontCache {
-    fonts: Vec<fontdue::Font>,
-    font_names: Vec<Font>,
+    fonts: RefCell<Vec<fontdue::Font>>,
+    names: RefCell<Vec<(String, FontState)>>,
+    /// Fonts we've already logged an error for.
+    warned: RefCell<Vec<String>>,
 }
 
 impl FontCache {
     fn new() -> Self {
-        FontCache{
-            fonts: vec![],
-            font_names: vec![],
+        FontCache {
+            fonts: RefCell::new(vec![]),
+            names: RefCell::new(vec![]),
+            warned: RefCell::new(vec![]),
+        }
+    }
+
+    fn register(&mut self, name: &str, source: FontSource) {
+        let names = self.names.get_mut();
+        names.retain(|(n, _)| n != name);
+        names.push((name.to_string(), FontState::Unloaded(source)));
+    }
+
+    /// Load the font with the given name, if that hasn't happened yet, and
+    /// return its index.
+    fn load(&self, name: &str) -> Result<usize> {
+        let mut names = self.names.borrow_mut();
+        let state = &mut names.iter_mut()
+            .find(|(n, _)| n == name)
+            .ok_or_else(|| anyhow!("There is no font named {:?}.", name))?
+            .1;
+
+        let source = match std::mem::replace(state, FontState::Failed) {
+            FontState::Loaded(index) => {
+                *state = FontState::Loaded(index);
+                return Ok(index);
+            },
+            FontState::Failed => bail!("Font {:?} couldn't be loaded.", name),
+            FontState::Unloaded(source) => source,
+        };
+
+        let font = match source {
+            FontSource::File(path) => fs::read(&path)
+                .with_context(|| format!("Can't read font file {:?}", path))
+                .and_then(|data| parse_font(&data)),
+            FontSource::Bytes(data) => parse_font(&data),
+        }.with_context(|| format!("Can't load font {:?}", name))?;
+
+        let mut fonts = self.fonts.borrow_mut();
+        fonts.push(font);
+        *state = FontState::Loaded(fonts.len() - 1);
+        Ok(fonts.len() - 1)
+    }
+
+    /// The index of the font with the given name. Falls back to
+    /// [DEFAULT_FONT] if the font doesn't exist or can't be loaded.
+    fn index(&self, name: &str) -> usize {
+        match self.load(name) {
+            Ok(index) => index,
+            Err(e) => {
+                // Views measure themselves over and over again, only complain
+                // the first time around.
+                let mut warned = self.warned.borrow_mut();
+                if !warned.iter().any(|n| n == name) {
+                    log::error!("{:#}, falling back to {}", e, DEFAULT_FONT);
+                    warned.push(name.to_string());
+                }
+                // Surface::new loads the default font before anything else.
+                0
+            },
         }
     }
 
-    fn add(&mut self, name: Font, font: fontdue::Font) {
-        self.fonts.push(font);
-        self.font_names.push(name);
+    fn fonts(&self) -> Ref<'_, Vec<fontdue::Font>> {
+        self.fonts.borrow()
+    }
+
+    fn font(&self, index: usize) -> Ref<'_, fontdue::Font> {
+        Ref::map(self.fonts.borrow(), |fonts| &fonts[index])
     }
 
-    fn fonts(&self) -> &[fontdue::Font] {
-        self.fonts.as_slice()
+}
+
+fn parse_font(data: &[u8]) -> Result<fontdue::Font> {
+    fontdue::Font::from_bytes(data, fontdue::FontSettings::default())
+        .map_err(|str| anyhow!(str))
+}
+
+const BLACK: image::Rgb<u8> = image::Rgb([0, 0, 0]);
+const WHITE: image::Rgb<u8> = image::Rgb([255, 255, 255]);
+const RED: image::Rgb<u8> = image::Rgb([255, 0, 0]);
+const YELLOW: image::Rgb<u8> = image::Rgb([255, 255, 0]);
+const DARK_GRAY: image::Rgb<u8> = image::Rgb([85, 85, 85]);
+const LIGHT_GRAY: image::Rgb<u8> = image::Rgb([170, 170, 170]);
+
+/// The colors [Surface::layout_preview] outlines frames with.
+const DEBUG_FRAME_COLOR: image::Rgb<u8> = RED;
+const DEBUG_PADDING_COLOR: image::Rgb<u8> = image::Rgb([0, 0, 255]);
+const DEBUG_SPACER_COLOR: image::Rgb<u8> = image::Rgb([0, 200, 0]);
+
+/// The set of colors a panel is able to display. Most OpenEPaperLink tags are
+/// black/white/red panels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Palette {
+    /// Plain 1-bit black and white.
+    BlackWhite,
+    /// Black and white with red as the accent color.
+    BlackWhiteRed,
+    /// Black and white with yellow as the accent color.
+    BlackWhiteYellow,
+    /// Black, white and two shades of gray.
+    Gray4,
+}
+
+impl Palette {
+    /// All colors of this palette.
+    pub fn colors(&self) -> &'static [image::Rgb<u8>] {
+        match self {
+            Palette::BlackWhite => &[BLACK, WHITE],
+            Palette::BlackWhiteRed => &[BLACK, WHITE, RED],
+            Palette::BlackWhiteYellow => &[BLACK, WHITE, YELLOW],
+            Palette::Gray4 => &[BLACK, DARK_GRAY, LIGHT_GRAY, WHITE],
+        }
     }
 
-    fn font(&self, name: Font) -> &fontdue::Font {
-        let font_index = self.font_names.iter().position(|&n| n == name).unwrap();
-        &self.fonts[font_index]
+    /// The accent color of this palette. Palettes without an accent color fall
+    /// back to black.
+    pub fn accent(&self) -> image::Rgb<u8> {
+        match self {
+            Palette::BlackWhiteRed => RED,
+            Palette::BlackWhiteYellow => YELLOW,
+            Palette::BlackWhite | Palette::Gray4 => BLACK,
+        }
     }
 
-    /// Construct a new [fontdue::layout::TextStyle] with the correct set of
-    /// fonts and the correct font index. Used by other views, not external
-    /// consumers of the module.
-    fn text_style<'a>(&self, text: &'a str, size: f32, font: Font) -> TextStyle<'a> {
-        let font_index = self.font_names.iter().position(|&n| n == font).unwrap(); 
-        TextStyle::new(text, size, font_index)
+    /// Find the palette color closest to `color` (by Euclidean distance in RGB
+    /// space).
+    pub fn closest(&self, color: image::Rgb<u8>) -> image::Rgb<u8> {
+        *self.colors()
+            .iter()
+            .min_by_key(|c| color_distance(**c, color))
+            .unwrap()
     }
 }
 
+/// Squared Euclidean distance between two colors.
+fn color_distance(a: image::Rgb<u8>, b: image::Rgb<u8>) -> u32 {
+    a.0.iter()
+        .zip(b.0.iter())
+        .map(|(&x, &y)| (x as i32 - y as i32).pow(2) as u32)
+        .sum()
+}
+
+/// A color to draw with. Views don't use RGB values directly, since those would
+/// have to match the panel exactly. Instead, they draw using one of these and
+/// the [Surface] resolves them using its [Palette].
 #[derive(Clone, Copy, Debug, PartialEq)]
-/// The set of fonts that are available to use.
-pub enum Font {
-    Roboto,
-    PlayfairDisplay,
+pub enum Color {
+    Black,
+    White,
+    /// The accent color of the panel, e.g. red on a black/white/red panel. On
+    /// panels without an accent color, this is black.
+    Accent,
+}
+
+/// A view that doesn't fit into the space it's offered, as reported by
+/// [View::layout]. Module authors can use this to react to layouts that don't
+/// work out, e.g. by using a smaller font or dropping a view, instead of
+/// drawing a broken screen.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Overflow {
+    /// The names of the views from the one `layout` was called on down to the
+    /// one that overflowed, e.g. `["Grid", "HStack", "Text"]`.
+    pub path: Vec<String>,
+    /// How many pixels too wide the view is, or 0 if its width fits.
+    pub width_excess: u32,
+    /// How many pixels too tall the view is, or 0 if its height fits.
+    pub height_excess: u32,
+}
+
+impl Overflow {
+    /// The name of the view that overflowed.
+    pub fn view(&self) -> &str {
+        self.path.last().map(String::as_str).unwrap_or("")
+    }
+
+    /// Check that `bounds` fit into `available`, blaming the view named `view`
+    /// if they don't.
+    fn check(view: &str, bounds: Bounds, available: Bounds) -> std::result::Result<(), Overflow> {
+        let width_excess = bounds.width.saturating_sub(available.width);
+        let height_excess = bounds.height.saturating_sub(available.height);
+        if width_excess == 0 && height_excess == 0 {
+            return Ok(());
+        }
+        Err(Overflow { path: vec![view.to_string()], width_excess, height_excess })
+    }
+
+    /// The same overflow, as seen from the view named `parent`.
+    fn inside(mut self, parent: &str) -> Overflow {
+        self.path.insert(0, parent.to_string());
+        self
+    }
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is ", self.view())?;
+        if self.width_excess > 0 {
+            write!(f, "{} pixels too wide", self.width_excess)?;
+        }
+        if self.width_excess > 0 && self.height_excess > 0 {
+            write!(f, " and ")?;
+        }
+        if self.height_excess > 0 {
+            write!(f, "{} pixels too tall", self.height_excess)?;
+        }
+        if self.path.len() > 1 {
+            write!(f, " (in {})", self.path[..self.path.len() - 1].join(" > "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// [View::layout] for a view with children: lay out each child in the bounds
+/// it's offered, then check the view itself.
+fn lay_out_children<'a>(
+    view: &(impl View + ?Sized),
+    surface: &Surface,
+    suggested_bounds: Bounds,
+    children: impl IntoIterator<Item = (&'a dyn View, Bounds)>,
+) -> std::result::Result<Bounds, Overflow> {
+    for (child, child_bounds) in children {
+        child.layout(surface, child_bounds).map_err(|o| o.inside(view.name()))?;
+    }
+    let bounds = view.bounds(surface, suggested_bounds);
+    Overflow::check(view.name(), bounds, suggested_bounds)?;
+    Ok(bounds)
 }
 
 /// A sizing hing for calculating the bounds of a [View]. See the remarks on
 /// [View] for how to interpret this.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
 pub enum SizingHint {
     /// The view should size itself to its own optimal size.
     Optimal,
@@ -291,6 +764,11 @@ impl Bounds {
             hint: self.hint,
         }
     }
+
+    /// Create a copy of the current bounds with width and height swapped.
+    pub fn transposed(&self) -> Self {
+        self.copy_hint(self.height, self.width)
+    }
 }
 
 impl Sub for Bounds {
@@ -298,8 +776,8 @@ impl Sub for Bounds {
 
     fn sub(self, other: Self) -> Self {
         Bounds {
-            width: self.width.checked_sub(other.width).unwrap_or(0),
-            height: self.height.checked_sub(other.height).unwrap_or(0),
+            width: self.width.saturating_sub(other.width),
+            height: self.height.saturating_sub(other.height),
             hint: self.hint,
         }
     }
@@ -339,7 +817,122 @@ impl PartialOrd for Bounds {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+/// A rectangle on a [Surface], e.g. the area drawing is clipped to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    /// A rectangle of the given size with its top left corner at (`x`, `y`).
+    pub fn at(x: u32, y: u32, bounds: Bounds) -> Rect {
+        Rect::new(x, y, bounds.width, bounds.height)
+    }
+
+    /// Whether the pixel at the given coordinates is inside the rectangle.
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+    }
+
+    /// The area covered by both rectangles. If they don't overlap, that's an
+    /// empty rectangle.
+    pub fn intersection(&self, other: Rect) -> Rect {
+        let x = cmp::max(self.x, other.x);
+        let y = cmp::max(self.y, other.y);
+        let right = cmp::min(self.x.saturating_add(self.width), other.x.saturating_add(other.width));
+        let bottom = cmp::min(self.y.saturating_add(self.height), other.y.saturating_add(other.height));
+        Rect::new(x, y, right.saturating_sub(x), bottom.saturating_sub(y))
+    }
+
+    /// The rectangle with `padding` taken off its edges.
+    fn inset(&self, padding: Padding) -> Rect {
+        Rect::new(
+            self.x + padding.left,
+            self.y + padding.top,
+            self.width.saturating_sub(padding.left + padding.right),
+            self.height.saturating_sub(padding.top + padding.bottom),
+        )
+    }
+}
+
+/// Paint a one pixel outline along the inside of `rect`, ignoring pixels that
+/// are outside of the image.
+fn outline_rect(img: &mut RgbImage, rect: Rect, color: image::Rgb<u8>) {
+    if rect.width == 0 || rect.height == 0 {
+        return;
+    }
+    let (right, bottom) = (rect.x + rect.width - 1, rect.y + rect.height - 1);
+    let mut put = |x: u32, y: u32| {
+        if x < img.width() && y < img.height() {
+            img.put_pixel(x, y, color);
+        }
+    };
+    for x in rect.x..=right {
+        put(x, rect.y);
+        put(x, bottom);
+    }
+    for y in rect.y..=bottom {
+        put(rect.x, y);
+        put(right, y);
+    }
+}
+
+/// A view drawn while [Surface::debug_layout] was set: where it was drawn,
+/// with which bounds, and the views it contains. Serializes to JSON as e.g.
+/// `{"type": "Text", "x": 10, "y": 10, "width": 80, "height": 16, "hint":
+/// "Optimal", "padding": {...}, "slot": {...}, "spacer": false, "children":
+/// []}`.
+#[derive(Clone, Debug, Serialize)]
+pub struct LayoutNode {
+    /// The name of the view, see [View::name].
+    #[serde(rename = "type")]
+    pub view: String,
+    pub x: u32,
+    pub y: u32,
+    /// The width of the bounds the view was drawn with, including padding.
+    pub width: u32,
+    /// The height of the bounds the view was drawn with, including padding.
+    pub height: u32,
+    pub hint: SizingHint,
+    pub padding: Padding,
+    /// The space the parent allocated for the view, i.e. the clip rectangle
+    /// it was drawn with.
+    pub slot: Rect,
+    /// Whether the view fills its slot like a [Spacer], see [View::is_spacer].
+    pub spacer: bool,
+    pub children: Vec<LayoutNode>,
+}
+
+impl LayoutNode {
+    fn new(view: &dyn View, x: u32, y: u32, bounds: Bounds, slot: Rect) -> LayoutNode {
+        LayoutNode {
+            view: view.name().to_string(),
+            x,
+            y,
+            width: bounds.width,
+            height: bounds.height,
+            hint: bounds.hint,
+            padding: view.padding_data(),
+            slot,
+            spacer: view.is_spacer(),
+            children: Vec::new(),
+        }
+    }
+
+    /// The area the view was drawn in.
+    pub fn frame(&self) -> Rect {
+        Rect::new(self.x, self.y, self.width, self.height)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize)]
 /// The padding of a [View].
 pub struct Padding {
     left: u32,
@@ -413,9 +1006,41 @@ pub trait View {
     /// available space optimally.
     fn bounds(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds;
 
+    /// Lay out this view in `suggested_bounds` without drawing it, and check
+    /// that everything fits. Returns the view's bounds, or the first view that
+    /// doesn't fit into the space it's offered as an [Overflow]. Containers
+    /// check their children against the space they allocate for them before
+    /// checking themselves, so the error points at the innermost culprit.
+    ///
+    /// The default implementation checks [bounds] against `suggested_bounds`.
+    /// Views that contain other views should override this and call
+    /// `layout` on their children.
+    fn layout(&self, surface: &Surface, suggested_bounds: Bounds) -> std::result::Result<Bounds, Overflow> {
+        let bounds = self.bounds(surface, suggested_bounds);
+        Overflow::check(self.name(), bounds, suggested_bounds)?;
+        Ok(bounds)
+    }
+
+    /// A short name for the view, used in [Overflow]s. Defaults to the name of
+    /// the type.
+    fn name(&self) -> &str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    /// Whether this view takes up all the space its parent offers along at
+    /// least one axis, like a [Spacer] does. The layout preview outlines the
+    /// space these views were allocated, see [Surface::layout_preview].
+    fn is_spacer(&self) -> bool {
+        false
+    }
+
     /// Draw the contents of this view to the given [Surface] at the given
     /// coordinates. Try to stick to the suggested bounds, although those are
-    /// really just a suggestion, see [bounds] for more information.
+    /// really just a suggestion, see [bounds] for more information. Draw
+    /// using [Surface::set_pixel] or [Surface::blend_pixel], so anything
+    /// outside of the space the parent allocated is clipped. Draw child views
+    /// using [Surface::draw_view], so they show up in the layout tree.
     fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds);
 
     /// Read the padding data for this view. This is usually really just a
@@ -443,6 +1068,7 @@ pub trait View {
 }
 
 /// Horizontal alignment.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum HAlign {
     Left,
     Center,
@@ -524,7 +1150,7 @@ impl VStack {
             })
             .zip(0..self.views.len())
             .collect();
-        flexibility.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        flexibility.sort_by_key(|f| f.0);
 
         // To determine our initial height, ask all child views for how much
         // space they want giving the sizing hint in `suggested_bounds`. Then
@@ -552,39 +1178,29 @@ impl VStack {
             },
         };
         let mut initial_height = cmp::min(suggested_bounds.height, initial_height_from_views);
-        let spacing_height = if self.views.len() > 0 {
+        let spacing_height = if !self.views.is_empty() {
             (self.views.len() as u32 - 1) * self.spacing
         } else {
             0
         };
-        if initial_height > suggested_bounds.height - spacing_height {
-            initial_height = suggested_bounds.height - spacing_height;
-        }
+        initial_height = cmp::min(initial_height, suggested_bounds.height.saturating_sub(spacing_height));
 
         let mut leftover_height = initial_height;
-        let mut temp_heights = Vec::<(usize, u32)>::new();
-        for i in 0..flexibility.len() {
-            let view_index = flexibility.get(i).unwrap().1;
-            let view = self.views.get(view_index).unwrap();
+        let mut heights = vec![0; self.views.len()];
+        for (i, &(_, view_index)) in flexibility.iter().enumerate() {
+            let view = &self.views[view_index];
             let views_left = (flexibility.len() - i) as u32;
             let suggestion = leftover_height / views_left;
             let actual_height = view
                 .bounds(surface, suggested_bounds.height_adjusted(suggestion))
                 .height;
 
-            temp_heights.push((view_index, actual_height));
-
-            if (leftover_height as i32) - (actual_height as i32) < 0 {
-                leftover_height = 0;
-            } else {
-                leftover_height -= actual_height;
-            }
+            heights[view_index] = actual_height;
+            leftover_height = leftover_height.saturating_sub(actual_height);
         }
 
         let mut y_off = 0;
-        for i in 0..self.views.len() {
-            let view_index = i;
-            let height = temp_heights.iter().find(|h| h.0 == view_index).unwrap().1;
+        for (view_index, height) in heights.into_iter().enumerate() {
             result.push((view_index, y_off, height));
             y_off += height + self.spacing;
         }
@@ -612,16 +1228,21 @@ impl View for VStack {
         Bounds::new(width, total_height)
     }
 
-    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
-        let max_x = x + suggested_bounds.width - self.padding_data().left - self.padding_data().right;
-
+    fn layout(&self, surface: &Surface, suggested_bounds: Bounds) -> std::result::Result<Bounds, Overflow> {
         let placement_bounds = suggested_bounds - self.padding_data().bounds();
         let placements = self.placements_and_heights(surface, placement_bounds);
+        let children = self.views.iter().zip(placements).map(|(view, placement)| {
+            (view.as_ref(), suggested_bounds.copy_hint(placement_bounds.width, placement.2))
+        });
+        lay_out_children(self, surface, suggested_bounds, children)
+    }
 
-        for i in 0..self.views.len() {
-            let view = self.views.get(i).unwrap();
-            let placement = placements.get(i).unwrap();
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let placement_bounds = suggested_bounds - self.padding_data().bounds();
+        let max_x = x + self.padding_data().left + placement_bounds.width;
+        let placements = self.placements_and_heights(surface, placement_bounds);
 
+        for (view, placement) in self.views.iter().zip(placements) {
             let placed_bounds = suggested_bounds.copy_hint(
                 placement_bounds.width,
                 placement.2,
@@ -633,11 +1254,13 @@ impl View for VStack {
 
             let view_x = match self.align {
                 HAlign::Left => x + self.padding_data().left,
-                HAlign::Right => max_x - child_bounds.width,
-                HAlign::Center => x + self.padding_data().left + (suggested_bounds.width - child_bounds.width) / 2,
+                HAlign::Right => max_x.saturating_sub(child_bounds.width),
+                HAlign::Center => x + self.padding_data().left + placement_bounds.width.saturating_sub(child_bounds.width) / 2,
             };
 
-            view.draw(surface, view_x, y + self.padding_data().top + placement.1, child_bounds);
+            let view_y = y + self.padding_data().top + placement.1;
+            let slot = Rect::new(x + self.padding_data().left, view_y, placement_bounds.width, placement.2);
+            surface.with_clip(slot, |surface| surface.draw_view(view.as_ref(), view_x, view_y, child_bounds));
         }
     }
 
@@ -651,6 +1274,7 @@ impl View for VStack {
 }
 
 /// Vertical alignment.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum VAlign {
     Top,
     Center,
@@ -717,7 +1341,7 @@ impl HStack {
             })
             .zip(0..self.views.len())
             .collect();
-        flexibility.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        flexibility.sort_by_key(|f| f.0);
 
         // To determine our initial width, ask all child views for how much
         // space they want giving the sizing hint in `suggested_bounds`. Then
@@ -745,39 +1369,29 @@ impl HStack {
             },
         };
         let mut initial_width = cmp::min(suggested_bounds.width, initial_width_from_views);
-        let spacing_width = if self.views.len() > 0 {
+        let spacing_width = if !self.views.is_empty() {
             (self.views.len() as u32 - 1) * self.spacing
         } else {
             0
         };
-        if initial_width > suggested_bounds.width - spacing_width {
-            initial_width = suggested_bounds.width - spacing_width;
-        }
+        initial_width = cmp::min(initial_width, suggested_bounds.width.saturating_sub(spacing_width));
 
         let mut leftover_width = initial_width;
-        let mut temp_widths = Vec::<(usize, u32)>::new();
-        for i in 0..flexibility.len() {
-            let view_index = flexibility.get(i).unwrap().1;
-            let view = self.views.get(view_index).unwrap();
+        let mut widths = vec![0; self.views.len()];
+        for (i, &(_, view_index)) in flexibility.iter().enumerate() {
+            let view = &self.views[view_index];
             let views_left = (flexibility.len() - i) as u32;
             let suggestion = leftover_width / views_left;
             let actual_width = view
                 .bounds(surface, suggested_bounds.width_adjusted(suggestion))
                 .width;
 
-            temp_widths.push((view_index, actual_width));
-
-            if (leftover_width as i32) - (actual_width as i32) < 0 {
-                leftover_width = 0;
-            } else {
-                leftover_width -= actual_width;
-            }
+            widths[view_index] = actual_width;
+            leftover_width = leftover_width.saturating_sub(actual_width);
         }
 
         let mut x_off = 0;
-        for i in 0..self.views.len() {
-            let view_index = i;
-            let width = temp_widths.iter().find(|h| h.0 == view_index).unwrap().1;
+        for (view_index, width) in widths.into_iter().enumerate() {
             result.push((view_index, x_off, width));
             x_off += width + self.spacing;
         }
@@ -805,16 +1419,21 @@ impl View for HStack {
         Bounds::new(total_width, height)
     }
 
-    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
-        let max_y = y + suggested_bounds.height - self.padding_data().top - self.padding_data().bottom;
-
+    fn layout(&self, surface: &Surface, suggested_bounds: Bounds) -> std::result::Result<Bounds, Overflow> {
         let placement_bounds = suggested_bounds - self.padding_data().bounds();
         let placements = self.placements_and_widths(surface, placement_bounds);
+        let children = self.views.iter().zip(placements).map(|(view, placement)| {
+            (view.as_ref(), suggested_bounds.copy_hint(placement.2, placement_bounds.height))
+        });
+        lay_out_children(self, surface, suggested_bounds, children)
+    }
 
-        for i in 0..self.views.len() {
-            let view = self.views.get(i).unwrap();
-            let placement = placements.get(i).unwrap();
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let placement_bounds = suggested_bounds - self.padding_data().bounds();
+        let max_y = y + self.padding_data().top + placement_bounds.height;
+        let placements = self.placements_and_widths(surface, placement_bounds);
 
+        for (view, placement) in self.views.iter().zip(placements) {
             let placed_bounds = suggested_bounds.copy_hint(
                 placement.2,
                 placement_bounds.height,
@@ -826,11 +1445,13 @@ impl View for HStack {
 
             let view_y = match self.align {
                 VAlign::Top => y + self.padding_data().top,
-                VAlign::Bottom => max_y - child_bounds.height,
-                VAlign::Center => y + self.padding_data().top + (suggested_bounds.height - child_bounds.height) / 2,
+                VAlign::Bottom => max_y.saturating_sub(child_bounds.height),
+                VAlign::Center => y + self.padding_data().top + placement_bounds.height.saturating_sub(child_bounds.height) / 2,
             };
 
-            view.draw(surface, x + self.padding_data().left + placement.1, view_y, child_bounds);
+            let view_x = x + self.padding_data().left + placement.1;
+            let slot = Rect::new(view_x, y + self.padding_data().top, placement.2, placement_bounds.height);
+            surface.with_clip(slot, |surface| surface.draw_view(view.as_ref(), view_x, view_y, child_bounds));
         }
     }
 
@@ -843,226 +1464,437 @@ impl View for HStack {
     }
 }
 
-enum Direction {
-    Horizontal,
-    Vertical,
+/// Alignment in both directions. Used to place views in a [ZStack].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Alignment {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
 }
 
-/// A spacer takes up all the available space while being maximally flexible: it
-/// will never cut into space that other views require, but will fill all truly
-/// available space.
+impl Alignment {
+    /// The horizontal component of this alignment.
+    pub fn horizontal(&self) -> HAlign {
+        match self {
+            Alignment::TopLeft | Alignment::Left | Alignment::BottomLeft => HAlign::Left,
+            Alignment::Top | Alignment::Center | Alignment::Bottom => HAlign::Center,
+            Alignment::TopRight | Alignment::Right | Alignment::BottomRight => HAlign::Right,
+        }
+    }
+
+    /// The vertical component of this alignment.
+    pub fn vertical(&self) -> VAlign {
+        match self {
+            Alignment::TopLeft | Alignment::Top | Alignment::TopRight => VAlign::Top,
+            Alignment::Left | Alignment::Center | Alignment::Right => VAlign::Center,
+            Alignment::BottomLeft | Alignment::Bottom | Alignment::BottomRight => VAlign::Bottom,
+        }
+    }
+
+    /// Find the coordinates for a view of size `child` aligned within a frame
+    /// of size `frame` whose top left corner is at (`frame_x`, `frame_y`).
+    fn place(&self, frame_x: u32, frame_y: u32, frame: Bounds, child: Bounds) -> (u32, u32) {
+        let free = frame - child;
+        let x = match self.horizontal() {
+            HAlign::Left => frame_x,
+            HAlign::Center => frame_x + free.width / 2,
+            HAlign::Right => frame_x + free.width,
+        };
+        let y = match self.vertical() {
+            VAlign::Top => frame_y,
+            VAlign::Center => frame_y + free.height / 2,
+            VAlign::Bottom => frame_y + free.height,
+        };
+        (x, y)
+    }
+}
+
+/// A stack of views layered on top of each other.
 ///
-/// Each spacer is flexible in one direction -- horizontally or vertically --
-/// and takes up zero space in the other.
-/// 
-/// It's usually used in conjunction with layout views such as [VStack] and
-/// [HStack].
-pub struct Spacer {
-    direction: Direction,
+/// Views are drawn in order, so the first view is at the very back and the last
+/// view is at the very front. The ZStack is as large as its largest child
+/// (plus padding), and each child is placed within that frame according to its
+/// own [Alignment].
+///
+/// This makes it easy to put a badge over an image or a timestamp in the corner
+/// of a full-bleed picture:
+///
+/// ```
+/// let mut zstack = ZStack::new();
+/// zstack.push(Box::new(picture), Alignment::Center);
+/// zstack.push(Box::new(timestamp), Alignment::BottomRight);
+/// ```
+///
+/// Flexible children, such as a [Spacer] or a flexible [Rectangle], take up
+/// the entire frame. That way, a flexible shape can be used as a background
+/// that fills all the space the ZStack's parent offers it.
+///
+/// Access the `views` field directly to manage the child views.
+pub struct ZStack {
+    pub views: Vec<(Box<dyn View>, Alignment)>,
+    padding: Padding,
 }
 
-impl Spacer {
-    /// Create a new horizontal spacer.
-    pub fn horizontal() -> Self {
-        Spacer { direction: Direction::Horizontal }
+impl ZStack {
+    pub fn new() -> Self {
+        ZStack {
+            views: Vec::new(),
+            padding: Padding::zero(),
+        }
     }
 
-    /// Create a new vertical spacer.
-    pub fn vertical() -> Self {
-        Spacer { direction: Direction::Vertical }
+    /// Add a view in front of all other views.
+    pub fn push(&mut self, view: Box<dyn View>, align: Alignment) {
+        self.views.push((view, align));
+    }
+
+    /// The frame that all child views are placed in, without padding.
+    fn frame(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let placement_bounds = suggested_bounds - self.padding_data().bounds();
+        let child_bounds: Vec<Bounds> = self.views
+            .iter()
+            .map(|(v, _)| v.bounds(surface, placement_bounds))
+            .collect();
+
+        Bounds::new(
+            child_bounds.iter().map(|b| b.width).max().unwrap_or(0),
+            child_bounds.iter().map(|b| b.height).max().unwrap_or(0),
+        )
     }
 }
 
-impl View for Spacer {
-    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
-        // A spacer takes up all the available space. That means that we just
-        // completely use up the available bounds, taking sizing hints into
-        // account.
+impl View for ZStack {
+    fn bounds(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        self.frame(surface, suggested_bounds) + self.padding_data().bounds()
+    }
 
-        if suggested_bounds.hint == SizingHint::ZeroSpace {
-            Bounds::new(0, 0)
-        } else {
-            match self.direction {
-                Direction::Vertical => suggested_bounds.width_adjusted(0),
-                Direction::Horizontal => suggested_bounds.height_adjusted(0),
-            }
-        }
+    fn layout(&self, surface: &Surface, suggested_bounds: Bounds) -> std::result::Result<Bounds, Overflow> {
+        let frame = self.frame(surface, suggested_bounds);
+        let children = self.views.iter().map(|(view, _)| {
+            (view.as_ref(), suggested_bounds.copy_hint(frame.width, frame.height))
+        });
+        lay_out_children(self, surface, suggested_bounds, children)
     }
 
-    fn draw(&self, _surface: &mut Surface, _x: u32, _y: u32, _suggested_bounds: Bounds) {
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let frame = self.frame(surface, suggested_bounds);
+        let frame_x = x + self.padding_data().left;
+        let frame_y = y + self.padding_data().top;
+
+        for (view, align) in self.views.iter() {
+            let child_bounds = view.bounds(
+                surface,
+                suggested_bounds.copy_hint(frame.width, frame.height),
+            );
+
+            let (view_x, view_y) = align.place(frame_x, frame_y, frame, child_bounds);
+            let slot = Rect::at(frame_x, frame_y, frame);
+            surface.with_clip(slot, |surface| surface.draw_view(view.as_ref(), view_x, view_y, child_bounds));
+        }
     }
 
     fn padding_data(&self) -> Padding {
-        Padding::zero()
+        self.padding
     }
 
-    fn set_padding_data(&mut self, _: Padding) {
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
     }
 }
 
-/// Renders text.
-///
-/// [Text] currently supports arbitrary font sizes and font wrapping. The choice
-/// of fonts is limited, see [Font]. 
-pub struct Text {
-    /// The text to render.
-    pub text: String,
+/// The size of a row or column in a [Grid].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Track {
+    /// A fixed number of pixels.
+    Fixed(u32),
+    /// A share of the space left over by all fixed and content-sized tracks.
+    /// The value is a weight: a `Fraction(2)` track gets twice as much space
+    /// as a `Fraction(1)` track. When zero-hinted, fractional tracks shrink to
+    /// their content instead.
+    Fraction(u32),
+    /// Exactly as large as the largest view in the track.
+    Content,
+}
 
-    /// The font size to use.
-    pub size: f32,
-    
-    /// The font to use.
-    pub font: Font,
+/// A view placed in a [Grid], along with its position, span, and alignment.
+pub struct GridCell {
+    pub view: Box<dyn View>,
+    pub row: usize,
+    pub column: usize,
+    /// The number of rows this cell spans. Defaults to 1.
+    pub row_span: usize,
+    /// The number of columns this cell spans. Defaults to 1.
+    pub column_span: usize,
+    /// Where to put the view within the cell. Defaults to
+    /// [Alignment::TopLeft].
+    pub align: Alignment,
+}
 
-    /// Wrap text to best fit the width of the suggested bounds. This wraps on
-    /// word boundaries. To really fit the suggested bounds, you'll probably
-    /// want to reduce font sizes until [bounds] returns something that you're
-    /// happy with.
-    pub wrap_text: bool,
+impl GridCell {
+    /// Create a new cell at the given row and column that spans a single row
+    /// and column.
+    pub fn new(view: Box<dyn View>, row: usize, column: usize) -> Self {
+        GridCell {
+            view,
+            row,
+            column,
+            row_span: 1,
+            column_span: 1,
+            align: Alignment::TopLeft,
+        }
+    }
+}
 
+/// A grid of views with rows and columns that line up.
+///
+/// Nesting [HStack]s in a [VStack] works for simple layouts, but each HStack
+/// distributes its space independently, so columns don't necessarily line up.
+/// A grid sizes every column (and row) once, for all the views in it. The size
+/// of each column and row is determined by a [Track]: fixed, a fraction of the
+/// leftover space, or sized to its content.
+///
+/// Views are added as [GridCell]s, which can span multiple rows or columns:
+///
+/// ```
+/// let mut grid = Grid::new(
+///     vec![Track::Content, Track::Fraction(1), Track::Fraction(1)],
+///     vec![Track::Content, Track::Content],
+/// );
+/// grid.cells.push(GridCell::new(Box::new(label), 0, 0));
+/// let mut value = GridCell::new(Box::new(temperature), 0, 1);
+/// value.column_span = 2;
+/// value.align = Alignment::Right;
+/// grid.cells.push(value);
+/// ```
+///
+/// Cells outside of the grid are ignored, spans reaching past the last row or
+/// column are cut off.
+pub struct Grid {
+    pub columns: Vec<Track>,
+    pub rows: Vec<Track>,
+    pub cells: Vec<GridCell>,
+    /// Space between two columns.
+    pub column_spacing: u32,
+    /// Space between two rows.
+    pub row_spacing: u32,
     padding: Padding,
 }
 
-impl Text {
-    pub fn new(text: String, size: f32, font: Font) -> Text {
-        Text {
-            text,
-            size,
-            font,
+/// The position and extent of a cell along one axis of a [Grid]: the index
+/// of the first track, the number of tracks, and the length of the view.
+type TrackSpan = (usize, usize, u32);
+
+impl Grid {
+    pub fn new(columns: Vec<Track>, rows: Vec<Track>) -> Self {
+        Grid {
+            columns,
+            rows,
+            cells: Vec::new(),
+            column_spacing: 0,
+            row_spacing: 0,
             padding: Padding::zero(),
-            wrap_text: false,
         }
     }
 
-    fn set_up_wrapping(&self, layout: &mut Layout, suggested_bounds: Bounds) {
-        let mut settings = layout.settings().clone();
-        settings.max_width = Some((suggested_bounds - self.padding_data().bounds()).width as f32);
-        layout.reset(&settings);
+    /// The cells that are actually within the grid, along with their spans
+    /// clamped to the grid: (cell, row_span, column_span).
+    fn placed_cells(&self) -> impl Iterator<Item = (&GridCell, usize, usize)> {
+        self.cells
+            .iter()
+            .filter(|c| c.row < self.rows.len() && c.column < self.columns.len())
+            .map(|c| {
+                let row_span = c.row_span.clamp(1, self.rows.len() - c.row);
+                let column_span = c.column_span.clamp(1, self.columns.len() - c.column);
+                (c, row_span, column_span)
+            })
     }
-}
 
-impl View for Text {
-    fn bounds(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds {
-        let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
-        if self.wrap_text {
-            self.set_up_wrapping(&mut layout, suggested_bounds);
-        }
-        layout.append(
-            surface.fonts.fonts(),
-            &surface.fonts.text_style(self.text.as_str(), self.size, self.font),
-        );
+    /// Determine the widths of all columns and the heights of all rows.
+    fn track_sizes(&self, surface: &Surface, suggested_bounds: Bounds) -> (Vec<u32>, Vec<u32>) {
+        let available = suggested_bounds - self.padding_data().bounds();
 
-        // Find the extent on the X and Y axes.
-        let glyphs = layout.glyphs();
-        let mut max_x: u32 = 0;
-        let mut max_y: u32 = 0;
-        for glyph in glyphs {
-            let right_edge = glyph.x as u32 + glyph.width as u32;
-            let bottom_edge = glyph.y as u32 + glyph.height as u32;
-            if right_edge > max_x {
-                max_x = right_edge;
-            }
-            if bottom_edge > max_y {
-                max_y = bottom_edge;
-            }
-        }
+        // Columns first. Each view is measured with all the space the grid has.
+        let column_spans: Vec<TrackSpan> = self.placed_cells()
+            .map(|(c, _, column_span)| {
+                let width = c.view.bounds(surface, available).width;
+                (c.column, column_span, width)
+            })
+            .collect();
+        let column_sizes = size_tracks(
+            &self.columns,
+            self.column_spacing,
+            available.width,
+            suggested_bounds.hint,
+            &column_spans,
+        );
 
-        // Now add padding.
-        max_x += self.padding_data().left + self.padding_data().right;
-        max_y += self.padding_data().top + self.padding_data().bottom;
+        // Now that we know the width of each column, measure the views again
+        // with the width of the cell they're in. That's the width they'll be
+        // drawn with, so this is what determines their height (think of
+        // wrapped text).
+        let row_spans: Vec<TrackSpan> = self.placed_cells()
+            .map(|(c, row_span, column_span)| {
+                let width = span_length(&column_sizes, self.column_spacing, c.column, column_span);
+                let height = c.view.bounds(surface, available.width_adjusted(width)).height;
+                (c.row, row_span, height)
+            })
+            .collect();
+        let row_sizes = size_tracks(
+            &self.rows,
+            self.row_spacing,
+            available.height,
+            suggested_bounds.hint,
+            &row_spans,
+        );
 
-        Bounds::new(max_x, max_y)
+        (column_sizes, row_sizes)
     }
+}
 
-    fn draw(&self, surface: &mut Surface, origin_x: u32, origin_y: u32, suggested_bounds: Bounds) {
-        let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
-        if self.wrap_text {
-            self.set_up_wrapping(&mut layout, suggested_bounds);
-        }
-        layout.append(
-            surface.fonts.fonts(),
-            &surface.fonts.text_style(self.text.as_str(), self.size, self.font),
-        );
-        let glyphs = layout.glyphs();
+/// The total length of `count` tracks starting at `start`, including the
+/// spacing in between.
+fn span_length(sizes: &[u32], spacing: u32, start: usize, count: usize) -> u32 {
+    let tracks: u32 = sizes[start..start + count].iter().sum();
+    tracks + spacing * (count as u32 - 1)
+}
 
-        let pad_origin_x = origin_x + self.padding_data().left;
-        let pad_origin_y = origin_y + self.padding_data().top;
+/// The offset of track `index`, relative to the first track.
+fn track_offset(sizes: &[u32], spacing: u32, index: usize) -> u32 {
+    sizes[..index].iter().map(|s| s + spacing).sum()
+}
 
-        let font_data = surface.fonts.font(self.font);
+/// Size the tracks along one axis of a [Grid]. `spans` contains the position
+/// and length of every view along that axis.
+fn size_tracks(tracks: &[Track], spacing: u32, available: u32, hint: SizingHint, spans: &[TrackSpan]) -> Vec<u32> {
+    // When zero-hinted, fractional tracks behave like content-sized tracks.
+    let sized_by_content = |track: &Track| match track {
+        Track::Content => true,
+        Track::Fraction(_) => hint == SizingHint::ZeroSpace,
+        Track::Fixed(_) => false,
+    };
+
+    let mut sizes: Vec<u32> = tracks
+        .iter()
+        .map(|t| match t {
+            Track::Fixed(size) => *size,
+            _ => 0,
+        })
+        .collect();
 
-        for glyph in glyphs {
-            let (metrics, bitmap) =
-                font_data.rasterize_indexed(glyph.key.glyph_index, glyph.key.px);
-
-            for y in 0..metrics.height {
-                for x in 0..metrics.width {
-                    let opacity = bitmap[y * metrics.width + x];
-                    let pixel_x: u32 = (glyph.x as u32 + x as u32 + pad_origin_x).try_into().unwrap();
-                    let pixel_y: u32 = (glyph.y as u32 + y as u32 + pad_origin_y).try_into().unwrap();
-                    if opacity > 30 {
-                        surface.img.put_pixel(
-                            pixel_x,
-                            pixel_y,
-                            image::Rgb([/*255 - opacity*/ 0, 0, 0]),
-                        );
-                    }
-                }
-            }
+    // Content-sized tracks are as large as their largest single-track view.
+    for &(start, _, length) in spans.iter().filter(|s| s.1 == 1) {
+        if sized_by_content(&tracks[start]) {
+            sizes[start] = cmp::max(sizes[start], length);
         }
     }
 
-    fn padding_data(&self) -> Padding {
-        self.padding
+    // Views spanning multiple tracks might need more space than those tracks
+    // have so far. If so, distribute the missing space evenly among the
+    // content-sized tracks they span. Fixed tracks stay fixed and fractional
+    // tracks get their space later.
+    for &(start, count, length) in spans.iter().filter(|s| s.1 > 1) {
+        let missing = length.saturating_sub(span_length(&sizes, spacing, start, count));
+        let flexible: Vec<usize> = (start..start + count)
+            .filter(|&i| sized_by_content(&tracks[i]))
+            .collect();
+        if missing == 0 || flexible.is_empty() {
+            continue;
+        }
+        let share = missing / flexible.len() as u32;
+        let remainder = missing % flexible.len() as u32;
+        for (n, &i) in flexible.iter().enumerate() {
+            sizes[i] += share + if n == 0 { remainder } else { 0 };
+        }
     }
 
-    fn set_padding_data(&mut self, new_padding: Padding) {
-        self.padding = new_padding;
+    if hint == SizingHint::ZeroSpace {
+        return sizes;
     }
-}
 
-/// Renders an image.
-pub struct Image {
-    image_data: RgbImage,
-    padding: Padding,
+    // Distribute whatever is left among the fractional tracks according to
+    // their weights. Rounding down the running total (rather than each
+    // share) makes sure we use up the leftover space exactly.
+    let total_spacing = spacing * (tracks.len() as u32).saturating_sub(1);
+    let leftover = available
+        .saturating_sub(total_spacing)
+        .saturating_sub(sizes.iter().sum());
+    let total_weight: u32 = tracks
+        .iter()
+        .map(|t| match t {
+            Track::Fraction(weight) => *weight,
+            _ => 0,
+        })
+        .sum();
+    if total_weight == 0 {
+        return sizes;
+    }
+
+    let mut weight_so_far = 0u64;
+    let mut assigned = 0u32;
+    for (i, track) in tracks.iter().enumerate() {
+        if let Track::Fraction(weight) = track {
+            weight_so_far += *weight as u64;
+            let target = (leftover as u64 * weight_so_far / total_weight as u64) as u32;
+            sizes[i] = target - assigned;
+            assigned = target;
+        }
+    }
+
+    sizes
 }
 
-impl Image {
-    /// Create a new image from the given reader ([std::io::Read]). The image
-    /// has to be a PNG.
-    pub fn from_data<R: Read + Seek>(data: R) -> Result<Image> {
-        let img = image::ImageReader::with_format(
-            BufReader::new(data),
-            ImageFormat::Png
-        )
-        .decode()
-        .with_context(|| "Error decoding image.")?;
+impl View for Grid {
+    fn bounds(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let (column_sizes, row_sizes) = self.track_sizes(surface, suggested_bounds);
+        let width = if column_sizes.is_empty() {
+            0
+        } else {
+            span_length(&column_sizes, self.column_spacing, 0, column_sizes.len())
+        };
+        let height = if row_sizes.is_empty() {
+            0
+        } else {
+            span_length(&row_sizes, self.row_spacing, 0, row_sizes.len())
+        };
 
-        Ok(Image {
-            image_data: img.into(),
-            padding: Padding::zero(),
-        })
+        Bounds::new(width, height) + self.padding_data().bounds()
     }
-}
 
-impl View for Image {
-    fn bounds(&self, _surface: &Surface, _suggested_bounds: Bounds) -> Bounds {
-        // Images need the space they need. No more, no less.
-        Bounds::new(self.image_data.width(), self.image_data.height()) +
-            self.padding_data().bounds() 
+    fn layout(&self, surface: &Surface, suggested_bounds: Bounds) -> std::result::Result<Bounds, Overflow> {
+        let (column_sizes, row_sizes) = self.track_sizes(surface, suggested_bounds);
+        let children = self.placed_cells().map(|(cell, row_span, column_span)| {
+            let frame = suggested_bounds.copy_hint(
+                span_length(&column_sizes, self.column_spacing, cell.column, column_span),
+                span_length(&row_sizes, self.row_spacing, cell.row, row_span),
+            );
+            (cell.view.as_ref(), frame)
+        });
+        lay_out_children(self, surface, suggested_bounds, children)
     }
 
-    fn draw(&self, surface: &mut Surface, x: u32, y: u32, _suggested_bounds: Bounds) {
-        let pad_origin_x = x + self.padding_data().left;
-        let pad_origin_y = y + self.padding_data().left;
-
-        for img_y in 0..self.image_data.height() {
-            for img_x in 0..self.image_data.width() {
-                surface.img.put_pixel(
-                    pad_origin_x + img_x,
-                    pad_origin_y + img_y,
-                    *self.image_data.get_pixel(img_x, img_y)
-                );
-            }
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let (column_sizes, row_sizes) = self.track_sizes(surface, suggested_bounds);
+        let origin_x = x + self.padding_data().left;
+        let origin_y = y + self.padding_data().top;
+
+        for (cell, row_span, column_span) in self.placed_cells() {
+            let cell_x = origin_x + track_offset(&column_sizes, self.column_spacing, cell.column);
+            let cell_y = origin_y + track_offset(&row_sizes, self.row_spacing, cell.row);
+            let frame = suggested_bounds.copy_hint(
+                span_length(&column_sizes, self.column_spacing, cell.column, column_span),
+                span_length(&row_sizes, self.row_spacing, cell.row, row_span),
+            );
+
+            let child_bounds = cell.view.bounds(surface, frame);
+            let (view_x, view_y) = cell.align.place(cell_x, cell_y, frame, child_bounds);
+            let slot = Rect::at(cell_x, cell_y, frame);
+            surface.with_clip(slot, |surface| surface.draw_view(cell.view.as_ref(), view_x, view_y, child_bounds));
         }
     }
 
@@ -1075,646 +1907,3749 @@ impl View for Image {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::rc::Rc;
-    use std::cell::RefCell;
+enum Direction {
+    Horizontal,
+    Vertical,
+}
 
-    struct DrawingRegister {
-        // id, x, y
-        drawings: RefCell<Vec<(u32, u32, u32, Bounds)>>,
+/// A spacer takes up all the available space while being maximally flexible: it
+/// will never cut into space that other views require, but will fill all truly
+/// available space.
+///
+/// Each spacer is flexible in one direction -- horizontally or vertically --
+/// and takes up zero space in the other.
+/// 
+/// It's usually used in conjunction with layout views such as [VStack] and
+/// [HStack].
+pub struct Spacer {
+    direction: Direction,
+}
+
+impl Spacer {
+    /// Create a new horizontal spacer.
+    pub fn horizontal() -> Self {
+        Spacer { direction: Direction::Horizontal }
     }
 
-    impl DrawingRegister {
-        pub fn new() -> DrawingRegister {
-            DrawingRegister {
-                drawings: RefCell::new(Vec::new()),
-            }
-        }
+    /// Create a new vertical spacer.
+    pub fn vertical() -> Self {
+        Spacer { direction: Direction::Vertical }
+    }
+}
 
-        pub fn register(&self, view_id: u32, x: u32, y: u32, suggested_bounds: Bounds) {
-            self.drawings.borrow_mut().push((view_id, x, y, suggested_bounds));
-        }
-        
-        pub fn was_drawn_at(&self, view_id: u32, x: u32, y: u32) -> bool {
-            for d in self.drawings.borrow().iter() {
-                if d.0 == view_id && d.1 == x && d.2 == y {
-                    return true;
-                }
-            }
+impl View for Spacer {
+    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        // A spacer takes up all the available space. That means that we just
+        // completely use up the available bounds, taking sizing hints into
+        // account.
 
-            false
+        if suggested_bounds.hint == SizingHint::ZeroSpace {
+            Bounds::new(0, 0)
+        } else {
+            match self.direction {
+                Direction::Vertical => suggested_bounds.width_adjusted(0),
+                Direction::Horizontal => suggested_bounds.height_adjusted(0),
+            }
         }
+    }
 
-        pub fn was_drawn_with_bounds(&self, view_id: u32, bounds: Bounds) -> bool {
-            for d in self.drawings.borrow().iter() {
-                if d.0 == view_id && d.3.width == bounds.width && d.3.height == bounds.height {
-                    return true;
-                }
-            }
+    fn is_spacer(&self) -> bool {
+        true
+    }
 
-            false
-        }
+    fn draw(&self, _surface: &mut Surface, _x: u32, _y: u32, _suggested_bounds: Bounds) {
     }
 
-    struct MonitorWrapper {
-        pub id: u32,
-        pub drawing_register: Rc<DrawingRegister>,
-        pub child: Box<dyn View>,
+    fn padding_data(&self) -> Padding {
+        Padding::zero()
     }
 
-    impl MonitorWrapper {
-        pub fn new(id: u32, drawing_register: Rc<DrawingRegister>, child: Box<dyn View>) -> Self {
-            MonitorWrapper { id, drawing_register, child }
-        }
+    fn set_padding_data(&mut self, _: Padding) {
     }
+}
 
-    impl View for MonitorWrapper {
-        fn bounds(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds {
-            self.child.bounds(surface, suggested_bounds)
-        }
+/// How to draw a shape: the color and width of its outline and an optional
+/// fill color.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShapeStyle {
+    /// The color of the outline.
+    pub color: Color,
+    /// The width of the outline in pixels. Set this to zero to draw the shape
+    /// without an outline.
+    pub stroke_width: u32,
+    /// The color to fill the shape with. `None` leaves the inside of the shape
+    /// untouched.
+    pub fill: Option<Color>,
+}
 
-        fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
-            self.drawing_register.as_ref().register(self.id, x, y, suggested_bounds);
-            self.child.draw(surface, x, y, suggested_bounds);
+impl ShapeStyle {
+    /// A one pixel wide black outline without a fill.
+    pub fn outline() -> Self {
+        ShapeStyle {
+            color: Color::Black,
+            stroke_width: 1,
+            fill: None,
         }
+    }
 
-        fn padding_data(&self) -> Padding {
-            Padding::zero()
+    /// A shape completely filled with the given color, without an outline.
+    pub fn filled(color: Color) -> Self {
+        ShapeStyle {
+            color,
+            stroke_width: 0,
+            fill: Some(color),
         }
+    }
+}
 
-        fn set_padding_data(&mut self, _: Padding) {
-        }
+/// Size one axis of a shape: shapes with a fixed size along that axis use it,
+/// flexible ones behave like a [Spacer] and take up all the suggested space
+/// (or nothing at all, if zero-hinted).
+fn flexible_length(fixed: Option<u32>, suggested: u32, hint: SizingHint) -> u32 {
+    match fixed {
+        Some(length) => length,
+        None if hint == SizingHint::ZeroSpace => 0,
+        None => suggested,
     }
+}
 
-    struct TestView {
-        pub id: u32,
-        pub width: u32,
-        pub height: u32,
-        pub drawing_register: Option<Rc<DrawingRegister>>,
-        pub padding: Padding,
+/// Whether the point (`px`, `py`) lies inside a `width` x `height` rectangle at
+/// the origin with corners rounded by `radius`.
+fn in_rounded_rect(px: f32, py: f32, width: f32, height: f32, radius: f32) -> bool {
+    if px < 0.0 || py < 0.0 || px > width || py > height {
+        return false;
     }
 
-    impl TestView {
-        pub fn new(width: u32, height: u32) -> TestView {
-            TestView {
-                id: 0,
-                width,
-                height,
-                drawing_register: None,
-                padding: Padding::zero(),
+    // Find the closest point in the rectangle that's inset by the radius. If
+    // we're within the radius of that point, we're inside the rounded corner
+    // (or not in a corner at all).
+    let closest_x = px.clamp(radius, (width - radius).max(radius));
+    let closest_y = py.clamp(radius, (height - radius).max(radius));
+    (px - closest_x).powi(2) + (py - closest_y).powi(2) <= radius.powi(2)
+}
+
+/// Paint a (rounded) rectangle with its top left corner at (`x`, `y`). This is
+/// used for all closed shapes: a rectangle is a rounded rectangle with a
+/// radius of zero and a circle is a square with a radius of half its width.
+fn paint_rounded_rect(surface: &mut Surface, x: u32, y: u32, width: u32, height: u32, radius: u32, style: ShapeStyle) {
+    let radius = cmp::min(radius, cmp::min(width, height) / 2) as f32;
+    let stroke = style.stroke_width as f32;
+    let (w, h) = (width as f32, height as f32);
+    let outline = surface.rgb(style.color);
+    let fill = style.fill.map(|c| surface.rgb(c));
+
+    for py in 0..height {
+        for px in 0..width {
+            // Test the pixel centers.
+            let (cx, cy) = (px as f32 + 0.5, py as f32 + 0.5);
+            if !in_rounded_rect(cx, cy, w, h, radius) {
+                continue;
             }
-        }
 
-        pub fn monitored(id: u32, register: Rc<DrawingRegister>, width: u32, height: u32) -> TestView {
-            TestView {
-                id,
-                width,
-                height,
-                drawing_register: Some(register),
-                padding: Padding::zero(),
+            let inside_outline = in_rounded_rect(
+                cx - stroke,
+                cy - stroke,
+                w - 2.0 * stroke,
+                h - 2.0 * stroke,
+                (radius - stroke).max(0.0),
+            );
+            let color = if inside_outline { fill } else { Some(outline) };
+            if let Some(color) = color {
+                surface.set_pixel(x + px, y + py, color);
             }
         }
     }
+}
 
-    impl View for TestView {
-        fn bounds(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds {
-            Bounds::new(self.width, self.height)
-        }
-
-        fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
-            if self.drawing_register.is_none() {
-                return;
+/// Paint a line between two points using Bresenham's algorithm.
+/// Every point of the line is painted as a square of `stroke_width` pixels,
+/// extending to the right and to the bottom.
+fn paint_line(surface: &mut Surface, from: (i64, i64), to: (i64, i64), stroke_width: u32, color: image::Rgb<u8>) {
+    let (mut x, mut y) = from;
+    let dx = (to.0 - x).abs();
+    let dy = -(to.1 - y).abs();
+    let step_x = if x < to.0 { 1 } else { -1 };
+    let step_y = if y < to.1 { 1 } else { -1 };
+    let mut error = dx + dy;
+
+    loop {
+        for brush_y in 0..stroke_width as i64 {
+            for brush_x in 0..stroke_width as i64 {
+                let (px, py) = (x + brush_x, y + brush_y);
+                if px >= 0 && py >= 0 {
+                    surface.set_pixel(px as u32, py as u32, color);
+                }
             }
-
-            self.drawing_register.as_ref().unwrap().register(self.id, x, y, suggested_bounds);
         }
 
-        fn padding_data(&self) -> Padding {
-            self.padding
+        if x == to.0 && y == to.1 {
+            break;
         }
-
-        fn set_padding_data(&mut self, new_padding: Padding) {
-            self.padding = new_padding;
+        let doubled_error = 2 * error;
+        if doubled_error >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled_error <= dx {
+            error += dx;
+            y += step_y;
         }
     }
+}
 
-    #[test]
-    fn test_empty_vstack_has_zero_width() {
-        let surface = Surface::new(300, 300).unwrap();
-        let vstack = VStack::new();
-        assert_eq!(0, vstack.bounds(&surface, surface.bounds()).width);
+/// A rectangle.
+///
+/// A rectangle can have a fixed width and height, or it can be flexible along
+/// one or both axes. Flexible rectangles behave just like a [Spacer]: they
+/// take up all the space their parent stack has available. That makes them
+/// useful as boxes or bars that span an entire section of the screen.
+pub struct Rectangle {
+    /// The width of the rectangle, or `None` for a flexible width.
+    pub width: Option<u32>,
+    /// The height of the rectangle, or `None` for a flexible height.
+    pub height: Option<u32>,
+    pub style: ShapeStyle,
+    padding: Padding,
+}
+
+impl Rectangle {
+    /// Create a new rectangle with a fixed size and a black outline.
+    pub fn new(width: u32, height: u32) -> Self {
+        Rectangle {
+            width: Some(width),
+            height: Some(height),
+            style: ShapeStyle::outline(),
+            padding: Padding::zero(),
+        }
     }
 
-    #[test]
-    fn test_vstack_has_maximum_width_of_all_children() {
-        let surface = Surface::new(300, 300).unwrap();
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(TestView::new(50, 10)));
-        vstack.views.push(Box::new(TestView::new(150, 10)));
-        vstack.views.push(Box::new(TestView::new(100, 10)));
-        assert_eq!(150, vstack.bounds(&surface, surface.bounds()).width);
+    /// Create a new rectangle that's flexible in both directions.
+    pub fn flexible() -> Self {
+        Rectangle {
+            width: None,
+            height: None,
+            style: ShapeStyle::outline(),
+            padding: Padding::zero(),
+        }
     }
+}
 
-    #[test]
-    fn test_empty_vstack_has_zero_height() {
-        let surface = Surface::new(300, 300).unwrap();
-        let mut vstack = VStack::new();
-        assert_eq!(0, vstack.bounds(&surface, surface.bounds()).height);
+impl View for Rectangle {
+    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let available = suggested_bounds - self.padding_data().bounds();
+        Bounds::new(
+            flexible_length(self.width, available.width, suggested_bounds.hint),
+            flexible_length(self.height, available.height, suggested_bounds.hint),
+        ) + self.padding_data().bounds()
     }
 
-    #[test]
-    fn test_vstack_has_total_height_of_all_children() {
-        let surface = Surface::new(300, 300).unwrap();
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(TestView::new(50, 50)));
-        vstack.views.push(Box::new(TestView::new(50, 100)));
-        vstack.views.push(Box::new(TestView::new(50, 10)));
-        assert_eq!(160, vstack.bounds(&surface, surface.bounds()).height);
+    fn is_spacer(&self) -> bool {
+        self.width.is_none() || self.height.is_none()
     }
 
-    #[test]
-    fn test_vstack_height_includes_spacing() {
-        let surface = Surface::new(300, 300).unwrap();
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(TestView::new(50, 50)));
-        vstack.views.push(Box::new(TestView::new(50, 100)));
-        vstack.views.push(Box::new(TestView::new(50, 10)));
-        vstack.spacing = 5;
-        assert_eq!(170, vstack.bounds(&surface, surface.bounds()).height);
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let size = self.bounds(surface, suggested_bounds) - self.padding_data().bounds();
+        paint_rounded_rect(
+            surface,
+            x + self.padding_data().left,
+            y + self.padding_data().top,
+            size.width,
+            size.height,
+            0,
+            self.style,
+        );
     }
-    
-    #[test]
-    fn test_vstack_draws_left_aligned_elements_at_original_x() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
-        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 50, 100)));
-        let bounds = surface.bounds();
-        vstack.draw(&mut surface, 100, 100, bounds);
-        assert!(register.was_drawn_at(1, 100, 100));
-        assert!(register.was_drawn_at(2, 100, 150));
+
+    fn padding_data(&self) -> Padding {
+        self.padding
     }
 
-    #[test]
-    fn test_vstack_draws_right_aligned_elements() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
-        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 100)));
-        vstack.align = HAlign::Right;
-        let bounds = surface.bounds();
-        vstack.draw(&mut surface, 100, 100, bounds - Bounds::new(100, 100));
-        assert!(register.was_drawn_at(1, 450, 100));
-        assert!(register.was_drawn_at(2, 425, 150));
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
     }
+}
 
-    #[test]
-    fn test_vstack_draws_center_aligned_elements() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
-        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 100)));
-        vstack.align = HAlign::Center;
-        let bounds = surface.bounds();
-        vstack.draw(&mut surface, 100, 100, bounds - Bounds::new(100, 100));
-        assert!(register.was_drawn_at(1, 275, 100));
-        assert!(register.was_drawn_at(2, 262, 150));
+/// A rectangle with rounded corners. Sizes itself just like a [Rectangle].
+pub struct RoundedRectangle {
+    /// The width of the rectangle, or `None` for a flexible width.
+    pub width: Option<u32>,
+    /// The height of the rectangle, or `None` for a flexible height.
+    pub height: Option<u32>,
+    /// The radius of the corners in pixels. This is capped at half the width
+    /// or height, whichever is smaller.
+    pub corner_radius: u32,
+    pub style: ShapeStyle,
+    padding: Padding,
+}
+
+impl RoundedRectangle {
+    /// Create a new rounded rectangle with a fixed size and a black outline.
+    pub fn new(width: u32, height: u32, corner_radius: u32) -> Self {
+        RoundedRectangle {
+            width: Some(width),
+            height: Some(height),
+            corner_radius,
+            style: ShapeStyle::outline(),
+            padding: Padding::zero(),
+        }
     }
 
-    #[test]
-    fn test_vstack_leaves_spacing_between_elements() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
-        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 100)));
-        vstack.spacing = 10;
-        let bounds = surface.bounds();
-        vstack.draw(&mut surface, 100, 100, bounds - Bounds::new(100, 100));
-        assert!(register.was_drawn_at(1, 100, 100));
-        assert!(register.was_drawn_at(2, 100, 160));
+    /// Create a new rounded rectangle that's flexible in both directions.
+    pub fn flexible(corner_radius: u32) -> Self {
+        RoundedRectangle {
+            width: None,
+            height: None,
+            corner_radius,
+            style: ShapeStyle::outline(),
+            padding: Padding::zero(),
+        }
     }
+}
 
-    #[test]
-    // TODO: Find a better name for this.
-    fn test_vstack_spacer() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
-        vstack.views.push(Box::new(Spacer::vertical()));
-        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 100)));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        assert_eq!(150, vstack.bounds(&surface, bounds.zero_hinted()).height);
-        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.optimally_hinted()).height);
-        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.infinitely_hinted()).height);
+impl View for RoundedRectangle {
+    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let available = suggested_bounds - self.padding_data().bounds();
+        Bounds::new(
+            flexible_length(self.width, available.width, suggested_bounds.hint),
+            flexible_length(self.height, available.height, suggested_bounds.hint),
+        ) + self.padding_data().bounds()
     }
 
-    #[test]
-    fn test_vstack_layouts_zero_views() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 0)));
-        vstack.views.push(Box::new(Spacer::vertical()));
-        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 100)));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        assert_eq!(100, vstack.bounds(&surface, bounds.zero_hinted()).height);
-        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.optimally_hinted()).height);
-        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.infinitely_hinted()).height);
+    fn is_spacer(&self) -> bool {
+        self.width.is_none() || self.height.is_none()
     }
 
-    #[test]
-    fn test_vstack_layouts_views_that_are_too_big() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 100)));
-        vstack.views.push(Box::new(Spacer::vertical()));
-        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 50, 100)));
-        let bounds = Bounds::new(50, 50);
-        assert_eq!(200, vstack.bounds(&surface, bounds.zero_hinted()).height);
-        assert_eq!(200, vstack.bounds(&surface, bounds.optimally_hinted()).height);
-        assert_eq!(200, vstack.bounds(&surface, bounds.infinitely_hinted()).height);
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let size = self.bounds(surface, suggested_bounds) - self.padding_data().bounds();
+        paint_rounded_rect(
+            surface,
+            x + self.padding_data().left,
+            y + self.padding_data().top,
+            size.width,
+            size.height,
+            self.corner_radius,
+            self.style,
+        );
     }
 
-    #[test]
-    fn test_vstack_layouts_multiple_spacers_zero_hinted() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 100)));
-        vstack.views.push(Box::new(Spacer::vertical()));
-        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 50, 75)));
-        vstack.views.push(Box::new(Spacer::vertical()));
-        vstack.views.push(Box::new(TestView::monitored(3, register.clone(), 50, 50)));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        // Zero-hinted, so both spacers should just collapse.
-        assert_eq!(225, vstack.bounds(&surface, bounds.zero_hinted()).height);
-        vstack.draw(&mut surface, 0, 0, bounds.zero_hinted());
-        assert!(register.was_drawn_at(1, 0, 0));
-        assert!(register.was_drawn_at(2, 0, 100));
-        assert!(register.was_drawn_at(3, 0, 175));
+    fn padding_data(&self) -> Padding {
+        self.padding
     }
 
-    #[test]
-    fn test_vstack_layouts_multiple_spacers_optimally_hinted() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 100)));
-        vstack.views.push(Box::new(Spacer::vertical()));
-        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 50, 75)));
-        vstack.views.push(Box::new(Spacer::vertical()));
-        vstack.views.push(Box::new(TestView::monitored(3, register.clone(), 50, 50)));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        // Optimally-hinted, so let spacers expand up to max.
-        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.optimally_hinted()).height);
-        vstack.draw(&mut surface, 0, 0, bounds.optimally_hinted());
-        // The first one is just at the top. After that, we'd expect the first
-        // and second spacers to take up equal height, so 87 and 88 pixels.
-        // (400 - (100 + 75 + 50)) / 2 = (400 - 225) / 2 = 175 / 2 = 87.5
-        assert!(register.was_drawn_at(1, 0, 0));
-        assert!(register.was_drawn_at(2, 0, 187));
-        assert!(register.was_drawn_at(3, 0, 350));
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
     }
+}
 
-    #[test]
-    fn test_vstack_layouts_multiple_spacers_infinitely_hinted() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 100)));
-        vstack.views.push(Box::new(Spacer::vertical()));
-        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 50, 75)));
-        vstack.views.push(Box::new(Spacer::vertical()));
-        vstack.views.push(Box::new(TestView::monitored(3, register.clone(), 50, 50)));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        // Infinitely-hinted, so let spacers expand up to max.
-        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.infinitely_hinted()).height);
-        vstack.draw(&mut surface, 0, 0, bounds.infinitely_hinted());
-        // The first one is just at the top. After that, we'd expect the first
-        // and second spacers to take up equal height, so 87 and 88 pixels.
-        // (400 - (100 + 75 + 50)) / 2 = (400 - 225) / 2 = 175 / 2 = 87.5
-        assert!(register.was_drawn_at(1, 0, 0));
-        assert!(register.was_drawn_at(2, 0, 187));
-        assert!(register.was_drawn_at(3, 0, 350));
+/// A circle.
+///
+/// A circle either has a fixed diameter or it's flexible, in which case it
+/// takes up as much space as it can while staying round: its diameter is the
+/// smaller of the suggested width and height.
+pub struct Circle {
+    /// The diameter of the circle, or `None` for a flexible diameter.
+    pub diameter: Option<u32>,
+    pub style: ShapeStyle,
+    padding: Padding,
+}
+
+impl Circle {
+    /// Create a new circle with a fixed diameter and a black outline.
+    pub fn new(diameter: u32) -> Self {
+        Circle {
+            diameter: Some(diameter),
+            style: ShapeStyle::outline(),
+            padding: Padding::zero(),
+        }
     }
 
-    #[test]
-    fn test_vstack_layouts_purely_spacers_zero_hinted() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(Spacer::vertical()));
-        vstack.views.push(Box::new(Spacer::vertical()));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        // Zero-hinted, so the spacers should collapse to zero, which means the
-        // entire VStack collapses to zero.
-        assert_eq!(0, vstack.bounds(&surface, bounds.zero_hinted()).height);
+    /// Create a new circle with a flexible diameter.
+    pub fn flexible() -> Self {
+        Circle {
+            diameter: None,
+            style: ShapeStyle::outline(),
+            padding: Padding::zero(),
+        }
     }
+}
 
-    #[test]
-    fn test_vstack_layouts_purely_spacers_optimally_hinted() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(Spacer::vertical()));
-        vstack.views.push(Box::new(Spacer::vertical()));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        // Optimally-hinted, so spacers expand to the maximum possible.
-        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.optimally_hinted()).height);
+impl View for Circle {
+    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let available = suggested_bounds - self.padding_data().bounds();
+        let diameter = flexible_length(
+            self.diameter,
+            cmp::min(available.width, available.height),
+            suggested_bounds.hint,
+        );
+        Bounds::new(diameter, diameter) + self.padding_data().bounds()
     }
 
-    #[test]
-    fn test_vstack_layouts_purely_spacers_infinitely_hinted() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut vstack = VStack::new();
-        vstack.views.push(Box::new(Spacer::vertical()));
-        vstack.views.push(Box::new(Spacer::vertical()));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        // Infinitely-hinted, so spacers expand to the maximum possible.
-        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.infinitely_hinted()).height);
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let size = self.bounds(surface, suggested_bounds) - self.padding_data().bounds();
+        paint_rounded_rect(
+            surface,
+            x + self.padding_data().left,
+            y + self.padding_data().top,
+            size.width,
+            size.height,
+            size.width / 2,
+            self.style,
+        );
     }
 
-    #[test]
-    fn test_vstack_layouts_nested_vstack() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut inner_vstack = VStack::new();
-        inner_vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
-        inner_vstack.views.push(Box::new(Spacer::vertical()));
-        inner_vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 80, 80)));
-        let mut outer_vstack = VStack::new();
-        outer_vstack.views.push(Box::new(inner_vstack));
-        outer_vstack.views.push(Box::new(TestView::monitored(3, register.clone(), 100, 100)));
-        outer_vstack.views.push(Box::new(MonitorWrapper::new(4, register.clone(), Box::new(Spacer::vertical()))));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        outer_vstack.draw(&mut surface, 0, 0, bounds.infinitely_hinted());
-        // First, the least-flexible view — the test view 3 of height 100 — will
-        // be offered 133 pixels, but it'll only need 100 pixels. Then, 150 of
-        // the remaining 300 pixels will be offered to the inner vstack, since
-        // that's _slightly_ less flexible than the bottom spacer. It'll gladly
-        // take the entire 150 pixels, expanding its spacer to 20 pixels. After
-        // that, the bottom spacer will get the remaining 150 pixels.
-        assert!(register.was_drawn_at(1, 0, 0));
-        assert!(register.was_drawn_at(2, 0, 70));
-        assert!(register.was_drawn_at(3, 0, 150));
-        assert!(register.was_drawn_at(4, 0, 250));
+    fn padding_data(&self) -> Padding {
+        self.padding
     }
 
-    #[test]
-    fn test_empty_hstack_has_zero_height() {
-        let surface = Surface::new(300, 300).unwrap();
-        let hstack = HStack::new();
-        assert_eq!(0, hstack.bounds(&surface, surface.bounds()).height);
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
     }
+}
 
-    #[test]
-    fn test_empty_hstack_has_maximum_height_of_all_children() {
-        let surface = Surface::new(300, 300).unwrap();
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(TestView::new(10, 50)));
-        hstack.views.push(Box::new(TestView::new(10, 150)));
-        hstack.views.push(Box::new(TestView::new(10, 100)));
-        assert_eq!(150, hstack.bounds(&surface, surface.bounds()).height);
+/// A straight line between two points.
+///
+/// The points are relative to the top left corner of the view (after padding).
+/// The view is exactly as large as it needs to be to contain both points.
+pub struct Line {
+    pub from: (u32, u32),
+    pub to: (u32, u32),
+    pub color: Color,
+    /// The width of the line in pixels.
+    pub stroke_width: u32,
+    padding: Padding,
+}
+
+impl Line {
+    /// Create a new one pixel wide black line.
+    pub fn new(from: (u32, u32), to: (u32, u32)) -> Self {
+        Line {
+            from,
+            to,
+            color: Color::Black,
+            stroke_width: 1,
+            padding: Padding::zero(),
+        }
     }
+}
 
-    #[test]
-    fn test_empty_hstack_has_zero_width() {
-        let surface = Surface::new(300, 300).unwrap();
-        let mut hstack = HStack::new();
-        assert_eq!(0, hstack.bounds(&surface, surface.bounds()).width);
+impl View for Line {
+    fn bounds(&self, _surface: &Surface, _suggested_bounds: Bounds) -> Bounds {
+        Bounds::new(
+            cmp::max(self.from.0, self.to.0) + self.stroke_width,
+            cmp::max(self.from.1, self.to.1) + self.stroke_width,
+        ) + self.padding_data().bounds()
     }
 
-    #[test]
-    fn test_hstack_with_inflexible_children_expands_to_their_total_width() {
-        let surface = Surface::new(300, 300).unwrap();
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(TestView::new(50, 50)));
-        hstack.views.push(Box::new(TestView::new(100, 50)));
-        hstack.views.push(Box::new(TestView::new(10, 50)));
-        assert_eq!(160, hstack.bounds(&surface, surface.bounds()).width);
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, _suggested_bounds: Bounds) {
+        let origin_x = (x + self.padding_data().left) as i64;
+        let origin_y = (y + self.padding_data().top) as i64;
+        let color = surface.rgb(self.color);
+        paint_line(
+            surface,
+            (origin_x + self.from.0 as i64, origin_y + self.from.1 as i64),
+            (origin_x + self.to.0 as i64, origin_y + self.to.1 as i64),
+            self.stroke_width,
+            color,
+        );
     }
 
-    #[test]
-    fn test_hstack_width_includes_spacing() {
-        let surface = Surface::new(300, 300).unwrap();
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(TestView::new(50, 50)));
-        hstack.views.push(Box::new(TestView::new(100, 50)));
-        hstack.views.push(Box::new(TestView::new(10, 50)));
-        hstack.spacing = 5;
-        assert_eq!(170, hstack.bounds(&surface, surface.bounds()).width);
+    fn padding_data(&self) -> Padding {
+        self.padding
     }
 
-    #[test]
-    fn test_hstack_draws_top_aligned_elements_at_original_y() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
-        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 100, 50)));
-        let bounds = surface.bounds();
-        hstack.draw(&mut surface, 100, 100, bounds);
-        assert!(register.was_drawn_at(1, 100, 100));
-        assert!(register.was_drawn_at(2, 150, 100));
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
     }
+}
 
-    #[test]
-    fn test_hstack_draws_bottom_aligned_elements() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
-        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 100, 75)));
-        hstack.align = VAlign::Bottom;
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        hstack.draw(&mut surface, 100, 100, bounds);
-        assert!(register.was_drawn_at(1, 100, 450));
-        assert!(register.was_drawn_at(2, 150, 425));
+/// A divider is a line that stretches across its parent stack, e.g. to
+/// separate sections of a dashboard.
+///
+/// Just like a [Spacer], a divider is flexible in one direction: a horizontal
+/// divider takes up all the width it's offered (so it's usually used in a
+/// [VStack]), a vertical divider takes up all the height (use it in an
+/// [HStack]). In the other direction, it's exactly as thick as its stroke.
+/// Use padding to inset the line from the edges or to leave room around it.
+pub struct Divider {
+    direction: Direction,
+    pub color: Color,
+    /// The thickness of the line in pixels.
+    pub stroke_width: u32,
+    padding: Padding,
+}
+
+impl Divider {
+    /// Create a new horizontal divider.
+    pub fn horizontal() -> Self {
+        Divider {
+            direction: Direction::Horizontal,
+            color: Color::Black,
+            stroke_width: 1,
+            padding: Padding::zero(),
+        }
     }
 
-    #[test]
-    fn test_hstack_draws_center_aligned_elements() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
-        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 100, 75)));
-        hstack.align = VAlign::Center;
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        hstack.draw(&mut surface, 100, 100, bounds);
-        assert!(register.was_drawn_at(1, 100, 275));
-        assert!(register.was_drawn_at(1, 150, 262));
+    /// Create a new vertical divider.
+    pub fn vertical() -> Self {
+        Divider {
+            direction: Direction::Vertical,
+            color: Color::Black,
+            stroke_width: 1,
+            padding: Padding::zero(),
+        }
     }
+}
 
-    #[test]
-    fn test_hstack_leaves_spacing_between_elements() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
-        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 100, 75)));
-        hstack.spacing = 10;
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        hstack.draw(&mut surface, 100, 100, bounds);
-        assert!(register.was_drawn_at(1, 100, 100));
-        assert!(register.was_drawn_at(2, 160, 100));
+impl View for Divider {
+    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let available = suggested_bounds - self.padding_data().bounds();
+        let length = |suggested| flexible_length(None, suggested, suggested_bounds.hint);
+        let line = match self.direction {
+            Direction::Horizontal => Bounds::new(length(available.width), self.stroke_width),
+            Direction::Vertical => Bounds::new(self.stroke_width, length(available.height)),
+        };
+        line + self.padding_data().bounds()
     }
 
-    #[test]
-    fn test_hstack_expands_spacers() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 100, 75)));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        assert_eq!(150, hstack.bounds(&surface, bounds.zero_hinted()).width);
-        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.optimally_hinted()).width);
-        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.infinitely_hinted()).width);
+    fn is_spacer(&self) -> bool {
+        true
     }
 
-    #[test]
-    fn test_hstack_layouts_zero_views() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 0, 50)));
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 100, 75)));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        assert_eq!(100, hstack.bounds(&surface, bounds.zero_hinted()).width);
-        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.optimally_hinted()).width);
-        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.infinitely_hinted()).width);
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let size = self.bounds(surface, suggested_bounds) - self.padding_data().bounds();
+        paint_rounded_rect(
+            surface,
+            x + self.padding_data().left,
+            y + self.padding_data().top,
+            size.width,
+            size.height,
+            0,
+            ShapeStyle::filled(self.color),
+        );
     }
 
-    #[test]
-    fn test_hstack_layouts_views_that_are_too_big() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 100, 50)));
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 100, 50)));
-        let bounds = Bounds::new(50, 50);
-        assert_eq!(200, hstack.bounds(&surface, bounds.zero_hinted()).width);
-        assert_eq!(200, hstack.bounds(&surface, bounds.optimally_hinted()).width);
-        assert_eq!(200, hstack.bounds(&surface, bounds.infinitely_hinted()).width);
+    fn padding_data(&self) -> Padding {
+        self.padding
     }
 
-    #[test]
-    fn test_hstack_layouts_multiple_spacers_zero_hinted() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 100, 50)));
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 50)));
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        hstack.views.push(Box::new(TestView::monitored(3, register.clone(), 50, 50)));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        // Zero-hinted, so both spacers should just collapse.
-        assert_eq!(225, hstack.bounds(&surface, bounds.zero_hinted()).width);
-        hstack.draw(&mut surface, 0, 0, bounds.zero_hinted());
-        assert!(register.was_drawn_at(1, 0, 0));
-        assert!(register.was_drawn_at(2, 100, 0));
-        assert!(register.was_drawn_at(3, 175, 0));
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
     }
+}
 
-    #[test]
-    fn test_hstack_layouts_multiple_spacers_optimally_hinted() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 100, 50)));
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 50)));
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        hstack.views.push(Box::new(TestView::monitored(3, register.clone(), 50, 50)));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        // Optimally-hinted, so let spacers expand up to max.
-        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.optimally_hinted()).width);
-        hstack.draw(&mut surface, 0, 0, bounds.optimally_hinted());
-        // The first one is just at the top. After that, we'd expect the first
-        // and second spacers to take up equal height, so 87 and 88 pixels.
-        // (400 - (100 + 75 + 50)) / 2 = (400 - 225) / 2 = 175 / 2 = 87.5
-        assert!(register.was_drawn_at(1, 0, 0));
-        assert!(register.was_drawn_at(2, 187, 0));
-        assert!(register.was_drawn_at(3, 350, 0));
+/// Paints a background behind another view.
+///
+/// The background covers the child's entire frame, including the child's own
+/// padding, so the usual way to get a banner with some room around the text is
+/// to pad the child and then wrap it. Padding set on the [Background] itself is
+/// left empty, outside of the background.
+///
+/// ```
+/// let mut label = Text::new(String::from("BREAKING"), 13.0, "Roboto");
+/// label.color = Color::White;
+/// label.padding(Edge::Left, 4);
+/// label.padding(Edge::Right, 4);
+/// let banner = Background::new(Box::new(label), Color::Black);
+/// ```
+pub struct Background {
+    pub child: Box<dyn View>,
+    pub color: Color,
+    /// Round the corners of the background by this many pixels.
+    pub corner_radius: u32,
+    padding: Padding,
+}
+
+impl Background {
+    pub fn new(child: Box<dyn View>, color: Color) -> Self {
+        Background {
+            child,
+            color,
+            corner_radius: 0,
+            padding: Padding::zero(),
+        }
     }
+}
 
-    #[test]
-    fn test_hstack_layouts_multiple_spacers_infinitely_hinted() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 100, 50)));
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 50)));
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        hstack.views.push(Box::new(TestView::monitored(3, register.clone(), 50, 50)));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        // Infinitely-hinted, so let spacers expand up to max.
-        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.optimally_hinted()).width);
-        hstack.draw(&mut surface, 0, 0, bounds.optimally_hinted());
-        // The first one is just at the top. After that, we'd expect the first
-        // and second spacers to take up equal height, so 87 and 88 pixels.
-        // (400 - (100 + 75 + 50)) / 2 = (400 - 225) / 2 = 175 / 2 = 87.5
-        assert!(register.was_drawn_at(1, 0, 0));
-        assert!(register.was_drawn_at(2, 187, 0));
-        assert!(register.was_drawn_at(3, 350, 0));
+impl View for Background {
+    fn bounds(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        self.child.bounds(surface, suggested_bounds - self.padding_data().bounds()) +
+            self.padding_data().bounds()
     }
 
-    #[test]
-    fn test_hstack_layouts_purely_spacers_zero_hinted() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        // Zero-hinted, so the spacers should collapse to zero, which means the
-        // entire hstack collapses to zero.
-        assert_eq!(0, hstack.bounds(&surface, bounds.zero_hinted()).width);
+    fn layout(&self, surface: &Surface, suggested_bounds: Bounds) -> std::result::Result<Bounds, Overflow> {
+        let child = (self.child.as_ref(), suggested_bounds - self.padding_data().bounds());
+        lay_out_children(self, surface, suggested_bounds, [child])
     }
 
-    #[test]
-    fn test_hstack_layouts_purely_spacers_optimally_hinted() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        // Optimally-hinted, so spacers expand to the maximum possible.
-        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.optimally_hinted()).width);
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let frame = self.child.bounds(surface, suggested_bounds - self.padding_data().bounds());
+        let frame_x = x + self.padding_data().left;
+        let frame_y = y + self.padding_data().top;
+
+        paint_rounded_rect(
+            surface,
+            frame_x,
+            frame_y,
+            frame.width,
+            frame.height,
+            self.corner_radius,
+            ShapeStyle::filled(self.color),
+        );
+        surface.draw_view(self.child.as_ref(), frame_x, frame_y, frame);
     }
 
-    #[test]
-    fn test_hstack_layouts_purely_spacers_infinitely_hinted() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut hstack = HStack::new();
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        hstack.views.push(Box::new(Spacer::horizontal()));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        // Infinitely-hinted, so spacers expand to the maximum possible.
-        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.infinitely_hinted()).width);
+    fn padding_data(&self) -> Padding {
+        self.padding
     }
 
-    #[test]
-    fn test_hstack_layouts_nested_hstack() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// Draws a border around another view.
+///
+/// The border is drawn around the child's frame (including the child's own
+/// padding) and takes up space of its own: the view is `stroke_width` pixels
+/// larger than its child on every side. That way, the border never overlaps
+/// the child's content. Padding set on the [Border] itself is left empty,
+/// outside of the border.
+pub struct Border {
+    pub child: Box<dyn View>,
+    pub color: Color,
+    /// The width of the border in pixels.
+    pub stroke_width: u32,
+    /// Round the corners of the border by this many pixels. You'll probably
+    /// want to pad the child to keep its content out of the corners.
+    pub corner_radius: u32,
+    padding: Padding,
+}
+
+impl Border {
+    /// Wrap `child` in a one pixel wide black border.
+    pub fn new(child: Box<dyn View>) -> Self {
+        Border {
+            child,
+            color: Color::Black,
+            stroke_width: 1,
+            corner_radius: 0,
+            padding: Padding::zero(),
+        }
+    }
+
+    /// The space taken up by our own padding plus the border itself.
+    fn insets(&self) -> Bounds {
+        self.padding_data().bounds() + Bounds::new(2 * self.stroke_width, 2 * self.stroke_width)
+    }
+}
+
+impl View for Border {
+    fn bounds(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        self.child.bounds(surface, suggested_bounds - self.insets()) + self.insets()
+    }
+
+    fn layout(&self, surface: &Surface, suggested_bounds: Bounds) -> std::result::Result<Bounds, Overflow> {
+        let child = (self.child.as_ref(), suggested_bounds - self.insets());
+        lay_out_children(self, surface, suggested_bounds, [child])
+    }
+
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let frame = self.child.bounds(surface, suggested_bounds - self.insets());
+        let border_x = x + self.padding_data().left;
+        let border_y = y + self.padding_data().top;
+
+        surface.draw_view(
+            self.child.as_ref(),
+            border_x + self.stroke_width,
+            border_y + self.stroke_width,
+            frame,
+        );
+        paint_rounded_rect(
+            surface,
+            border_x,
+            border_y,
+            frame.width + 2 * self.stroke_width,
+            frame.height + 2 * self.stroke_width,
+            self.corner_radius,
+            ShapeStyle {
+                color: self.color,
+                stroke_width: self.stroke_width,
+                fill: None,
+            },
+        );
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// Inverts the region covered by another view, e.g. to turn black text on
+/// white into white text on black.
+///
+/// Only grays (including black and white) are inverted. Colored pixels, such as
+/// those drawn in the accent color, are left alone, so that accents stay
+/// accents. Like [Background], the inverted region is the child's frame,
+/// including the child's own padding.
+pub struct Invert {
+    pub child: Box<dyn View>,
+    padding: Padding,
+}
+
+impl Invert {
+    pub fn new(child: Box<dyn View>) -> Self {
+        Invert {
+            child,
+            padding: Padding::zero(),
+        }
+    }
+}
+
+impl View for Invert {
+    fn bounds(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        self.child.bounds(surface, suggested_bounds - self.padding_data().bounds()) +
+            self.padding_data().bounds()
+    }
+
+    fn layout(&self, surface: &Surface, suggested_bounds: Bounds) -> std::result::Result<Bounds, Overflow> {
+        let child = (self.child.as_ref(), suggested_bounds - self.padding_data().bounds());
+        lay_out_children(self, surface, suggested_bounds, [child])
+    }
+
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let frame = self.child.bounds(surface, suggested_bounds - self.padding_data().bounds());
+        let frame_x = x + self.padding_data().left;
+        let frame_y = y + self.padding_data().top;
+
+        surface.draw_view(self.child.as_ref(), frame_x, frame_y, frame);
+
+        let area = surface.clip_rect().intersection(Rect::at(frame_x, frame_y, frame));
+        for py in area.y..area.y + area.height {
+            for px in area.x..area.x + area.width {
+                let pixel = surface.img.get_pixel_mut(px, py);
+                let [r, g, b] = pixel.0;
+                if r == g && g == b {
+                    *pixel = image::Rgb([255 - r, 255 - g, 255 - b]);
+                }
+            }
+        }
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// A rotation by a multiple of 90 degrees, clockwise. In config files, it's
+/// written as the number of degrees: 0, 90, 180, or 270.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "u32")]
+pub enum Rotation {
+    #[default]
+    None,
+    Clockwise90,
+    Clockwise180,
+    Clockwise270,
+}
+
+impl TryFrom<u32> for Rotation {
+    type Error = String;
+
+    fn try_from(degrees: u32) -> std::result::Result<Self, Self::Error> {
+        match degrees {
+            0 => Ok(Rotation::None),
+            90 => Ok(Rotation::Clockwise90),
+            180 => Ok(Rotation::Clockwise180),
+            270 => Ok(Rotation::Clockwise270),
+            _ => Err(format!("Invalid rotation {}, has to be 0, 90, 180, or 270", degrees)),
+        }
+    }
+}
+
+impl Rotation {
+    /// Whether the rotation swaps width and height.
+    pub fn is_sideways(&self) -> bool {
+        matches!(self, Rotation::Clockwise90 | Rotation::Clockwise270)
+    }
+
+    /// Where the point (x, y) of an image with the given dimensions ends up
+    /// after rotating the image.
+    fn apply(&self, x: u32, y: u32, width: u32, height: u32) -> (u32, u32) {
+        match self {
+            Rotation::None => (x, y),
+            Rotation::Clockwise90 => (height - 1 - y, x),
+            Rotation::Clockwise180 => (width - 1 - x, height - 1 - y),
+            Rotation::Clockwise270 => (y, width - 1 - x),
+        }
+    }
+}
+
+/// Draws another view rotated by 90, 180, or 270 degrees, e.g. for vertical
+/// labels on narrow tags.
+///
+/// For sideways rotations, the child is offered the suggested bounds with
+/// width and height swapped, and the rotated view reports its size swapped
+/// back, so it fits into stacks like any other view: a rotated line of text
+/// is narrow and tall.
+pub struct Rotate {
+    pub child: Box<dyn View>,
+    pub rotation: Rotation,
+    padding: Padding,
+}
+
+impl Rotate {
+    pub fn new(child: Box<dyn View>, rotation: Rotation) -> Self {
+        Rotate {
+            child,
+            rotation,
+            padding: Padding::zero(),
+        }
+    }
+
+    /// Convert bounds between our coordinate space and the child's.
+    fn to_child(&self, bounds: Bounds) -> Bounds {
+        if self.rotation.is_sideways() {
+            bounds.transposed()
+        } else {
+            bounds
+        }
+    }
+}
+
+impl View for Rotate {
+    fn bounds(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let child_bounds = self.child.bounds(surface, self.to_child(suggested_bounds - self.padding_data().bounds()));
+        self.to_child(child_bounds) + self.padding_data().bounds()
+    }
+
+    fn layout(&self, surface: &Surface, suggested_bounds: Bounds) -> std::result::Result<Bounds, Overflow> {
+        let child_bounds = self.to_child(suggested_bounds - self.padding_data().bounds());
+        self.child.layout(surface, child_bounds).map_err(|mut overflow| {
+            // The child's axes are ours, turned sideways.
+            if self.rotation.is_sideways() {
+                std::mem::swap(&mut overflow.width_excess, &mut overflow.height_excess);
+            }
+            overflow.inside(self.name())
+        })?;
+        let bounds = self.bounds(surface, suggested_bounds);
+        Overflow::check(self.name(), bounds, suggested_bounds)?;
+        Ok(bounds)
+    }
+
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let frame_x = x + self.padding_data().left;
+        let frame_y = y + self.padding_data().top;
+        let child_bounds = self.child.bounds(surface, self.to_child(suggested_bounds - self.padding_data().bounds()));
+        let (width, height) = (child_bounds.width, child_bounds.height);
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        // Draw the child unrotated onto a scratch image, starting out with
+        // whatever is on the surface underneath, so blending works. We swap
+        // the scratch image into the surface for that, so the child has
+        // access to the surface's fonts and palette.
+        let target = |cx: u32, cy: u32| {
+            let (dx, dy) = self.rotation.apply(cx, cy, width, height);
+            (frame_x + dx, frame_y + dy)
+        };
+        let mut scratch = RgbImage::from_fn(width, height, |cx, cy| {
+            let (px, py) = target(cx, cy);
+            surface.img.get_pixel_checked(px, py).copied().unwrap_or(WHITE)
+        });
+        // The clip rectangles are in the surface's coordinates, so they don't
+        // apply to the scratch image. They're applied when copying the pixels
+        // back instead. The same goes for the frames recorded for debugging,
+        // so the child's views aren't recorded.
+        std::mem::swap(&mut surface.img, &mut scratch);
+        let clips = std::mem::take(&mut surface.clips);
+        let debug_layout = std::mem::replace(&mut surface.debug_layout, false);
+        self.child.draw(surface, 0, 0, child_bounds);
+        surface.debug_layout = debug_layout;
+        surface.clips = clips;
+        std::mem::swap(&mut surface.img, &mut scratch);
+
+        for (cx, cy, pixel) in scratch.enumerate_pixels() {
+            let (px, py) = target(cx, cy);
+            surface.set_pixel(px, py, *pixel);
+        }
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// How [Text] turns the glyph coverage computed by fontdue into pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextRendering {
+    /// Paint every pixel that's covered enough by a glyph in a solid color and
+    /// leave all other pixels alone. This looks crisp on 1-bit panels, but
+    /// thin strokes of small text can disappear.
+    Threshold,
+
+    /// Blend the glyph coverage into the background, which results in
+    /// anti-aliased, grayscale text. It's up to a later quantization step to
+    /// map the gray levels onto whatever the panel can actually display.
+    Grayscale,
+}
+
+/// Minimum glyph coverage (0-255) for a pixel to be painted when using
+/// [TextRendering::Threshold].
+const TEXT_COVERAGE_THRESHOLD: u8 = 30;
+
+/// How the lines of a multi-line [Text] are aligned with each other.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextAlignment {
+    Left,
+    Center,
+    Right,
+    /// Stretch the spaces of every line so that it's as wide as the widest
+    /// line. The last line of every paragraph is left-aligned.
+    Justify,
+}
+
+/// How a [Text] that has more lines than [Text::max_lines] is shortened. All
+/// strategies cut as little as possible.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Truncation {
+    /// Cut off the end of the text and add an ellipsis: "Bundestag besch…".
+    Tail,
+    /// Cut out the middle of the text and put an ellipsis there:
+    /// "Bundes…Haushalt".
+    Middle,
+    /// Cut off the end of the text after the last word that fits and add an
+    /// ellipsis: "Bundestag…".
+    Word,
+}
+
+/// Makes a [Text] pick the largest font size at which it fits into the space
+/// it's offered, see [Text::shrink_to_fit].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShrinkToFit {
+    pub min_size: f32,
+    pub max_size: f32,
+    pub search: FitSearch,
+}
+
+/// How [ShrinkToFit] looks for the right font size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FitSearch {
+    /// Start at the maximum size and go down in steps of the given size until
+    /// the text fits. Predictable sizes, but lots of layout passes for a big
+    /// range of sizes.
+    Step(f32),
+    /// Bisect the range of sizes, down to half a pixel. A non-positive
+    /// [FitSearch::Step] falls back to this.
+    Binary,
+}
+
+/// The font size (and, if even the minimum size is too large, the number of
+/// lines) that [ShrinkToFit] picked for the space a [Text] was offered.
+#[derive(Clone, Copy)]
+struct FittedSize {
+    width: u32,
+    height: u32,
+    fit: ShrinkToFit,
+    size: f32,
+    max_lines: Option<usize>,
+}
+
+/// Renders text.
+///
+/// [Text] currently supports arbitrary font sizes and font wrapping. Fonts are
+/// referenced by the name they were registered with on the [Surface]. Use
+/// [rendering] to choose between crisp, thresholded glyphs and anti-aliased
+/// grayscale ones (see [TextRendering]).
+///
+/// Lines are aligned relative to the widest line of the text (see
+/// [alignment]), which is also how wide the view is. Where the whole block of
+/// text ends up is up to the parent, as with every other view.
+pub struct Text {
+    /// The text to render.
+    pub text: String,
+
+    /// The font size to use.
+    pub size: f32,
+    
+    /// The name of the font to use, see [Surface::register_font_file] and
+    /// friends. If there's no such font, the text uses [DEFAULT_FONT].
+    pub font: String,
+
+    /// Fonts to use for characters that [font] doesn't have a glyph for, in
+    /// order. Each character uses the first font that has it. After these, the
+    /// surface's [Surface::fallback_fonts] are tried.
+    pub fallback_fonts: Vec<String>,
+
+    /// Wrap text to best fit the width of the suggested bounds. This wraps on
+    /// word boundaries. To really fit the suggested bounds, you'll probably
+    /// want to reduce font sizes until [bounds] returns something that you're
+    /// happy with.
+    pub wrap_text: bool,
+
+    /// How to turn the glyph outlines into pixels. If this is `None`, the
+    /// text uses the surface's default ([Surface::text_rendering]).
+    pub rendering: Option<TextRendering>,
+
+    /// The color of the text. Defaults to [Color::Black].
+    pub color: Color,
+
+    /// How lines are aligned with each other. Only has an effect if there is
+    /// more than one line, be it because of wrapping or line breaks in the
+    /// text. Defaults to [TextAlignment::Left].
+    pub alignment: TextAlignment,
+
+    /// The distance between lines as a multiple of the font's line height.
+    /// Defaults to 1.0.
+    pub line_height: f32,
+
+    /// Extra space between characters, in pixels. Can be negative to tighten
+    /// the text. Note that wrapping doesn't take the extra space into account,
+    /// so wide letter spacing can push wrapped lines past the suggested width.
+    pub letter_spacing: f32,
+
+    /// The maximum number of lines to render. If the text needs more, it's
+    /// shortened according to [truncation]. Combined with [wrap_text] and a
+    /// limit of one line, this keeps a text from getting wider than the
+    /// suggested bounds.
+    pub max_lines: Option<usize>,
+
+    /// How the text is shortened to fit into [max_lines]. Defaults to
+    /// [Truncation::Tail].
+    pub truncation: Truncation,
+
+    /// Pick the font size automatically: the largest size between the minimum
+    /// and maximum at which the text fits into the bounds its parent offers.
+    /// [size] is ignored in that case. If the text doesn't fit even at the
+    /// minimum size, it's truncated (see [truncation]) to the number of lines
+    /// that do fit.
+    pub shrink_to_fit: Option<ShrinkToFit>,
+
+    /// The result of the last [shrink_to_fit] search. Views are measured many
+    /// times with the same bounds before they're drawn, so this saves us from
+    /// searching again every time. It doesn't notice changes to the text or
+    /// its style, only to the bounds and [shrink_to_fit] itself.
+    fitted_size: RefCell<Option<FittedSize>>,
+
+    padding: Padding,
+}
+
+impl Text {
+    pub fn new(text: String, size: f32, font: &str) -> Text {
+        Text {
+            text,
+            size,
+            font: font.to_string(),
+            fallback_fonts: Vec::new(),
+            padding: Padding::zero(),
+            wrap_text: false,
+            rendering: None,
+            color: Color::Black,
+            alignment: TextAlignment::Left,
+            line_height: 1.0,
+            letter_spacing: 0.0,
+            max_lines: None,
+            truncation: Truncation::Tail,
+            shrink_to_fit: None,
+            fitted_size: RefCell::new(None),
+        }
+    }
+
+    fn set_up_layout(&self, layout: &mut Layout, suggested_bounds: Bounds) {
+        let mut settings = *layout.settings();
+        if self.wrap_text {
+            settings.max_width = Some((suggested_bounds - self.padding_data().bounds()).width as f32);
+        }
+        settings.line_height = self.line_height;
+        layout.reset(&settings);
+    }
+
+    fn lay_out_str(&self, surface: &Surface, suggested_bounds: Bounds, text: &str, size: f32) -> Layout {
+        let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+        self.set_up_layout(&mut layout, suggested_bounds);
+        for (run, font) in font_runs(surface, text, &self.font, &self.fallback_fonts) {
+            layout.append(&surface.fonts.fonts(), &TextStyle::new(run, size, font));
+        }
+        layout
+    }
+
+    /// Find the longest shortened version of the text that fits into
+    /// `max_lines`.
+    fn truncate(&self, surface: &Surface, suggested_bounds: Bounds, size: f32, max_lines: usize) -> String {
+        let font = font_for(surface, &self.font, &self.fallback_fonts, '…');
+        let ellipsis = if surface.fonts.font(font).lookup_glyph_index('…') != 0 {
+            "…"
+        } else {
+            "..."
+        };
+        let chars: Vec<char> = self.text.chars().collect();
+        // Byte offsets of the end of every word, for Truncation::Word.
+        let word_ends: Vec<usize> = self.text.char_indices()
+            .zip(self.text.chars().skip(1).map(Some).chain([None]))
+            .filter(|((_, c), next)| !c.is_whitespace() && next.is_none_or(|n| n.is_whitespace()))
+            .map(|((i, c), _)| i + c.len_utf8())
+            .collect();
+
+        // Build the shortened text keeping `n` characters (or words).
+        let candidate = |n: usize| -> String {
+            match self.truncation {
+                Truncation::Tail => {
+                    let head: String = chars[..n].iter().collect();
+                    format!("{}{}", head.trim_end(), ellipsis)
+                },
+                Truncation::Middle => {
+                    let head: String = chars[..n.div_ceil(2)].iter().collect();
+                    let tail: String = chars[chars.len() - n / 2..].iter().collect();
+                    format!("{}{}{}", head.trim_end(), ellipsis, tail.trim_start())
+                },
+                Truncation::Word => {
+                    let end = if n == 0 { 0 } else { word_ends[n - 1] };
+                    format!("{}{}", &self.text[..end], ellipsis)
+                },
+            }
+        };
+        let fits = |text: &str| {
+            line_count(&self.lay_out_str(surface, suggested_bounds, text, size)) <= max_lines
+        };
+
+        // Binary search for the largest number of characters (or words) that
+        // still fits. Keeping nothing but the ellipsis is the fallback.
+        let mut low = 0;
+        let mut high = match self.truncation {
+            Truncation::Word => word_ends.len(),
+            _ => chars.len(),
+        };
+        while low < high {
+            let mid = (low + high).div_ceil(2);
+            if fits(&candidate(mid)) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        candidate(low)
+    }
+
+    /// Lay out the text and return the positioned glyphs, relative to the
+    /// padded origin.
+    fn glyph_positions(&self, surface: &Surface, suggested_bounds: Bounds) -> Vec<GlyphPosition> {
+        let (size, max_lines) = match self.shrink_to_fit {
+            Some(fit) => self.fitted_size(surface, suggested_bounds, fit),
+            None => (self.size, self.max_lines),
+        };
+        self.glyph_positions_with(surface, suggested_bounds, size, max_lines)
+    }
+
+    /// Find the font size and the maximum number of lines to use for
+    /// [shrink_to_fit] in `suggested_bounds`.
+    fn fitted_size(&self, surface: &Surface, suggested_bounds: Bounds, fit: ShrinkToFit) -> (f32, Option<usize>) {
+        let available = suggested_bounds - self.padding_data().bounds();
+        if let Some(fitted) = *self.fitted_size.borrow() {
+            if fitted.width == available.width && fitted.height == available.height && fitted.fit == fit {
+                return (fitted.size, fitted.max_lines);
+            }
+        }
+
+        let fits = |glyphs: &[GlyphPosition]| {
+            let (width, height) = glyph_extent(glyphs);
+            width <= available.width && height <= available.height
+        };
+        let fits_at = |size: f32| fits(&self.glyph_positions_with(surface, suggested_bounds, size, self.max_lines));
+
+        let size = if fits_at(fit.max_size) {
+            fit.max_size
+        } else {
+            match fit.search {
+                FitSearch::Step(step) if step > 0.0 => {
+                    let mut size = fit.max_size - step;
+                    while size > fit.min_size && !fits_at(size) {
+                        size -= step;
+                    }
+                    size.max(fit.min_size)
+                },
+                _ => {
+                    // The largest size that fits is somewhere between the two.
+                    // Half a pixel is as precise as it gets.
+                    let (mut low, mut high) = (fit.min_size, fit.max_size);
+                    while high - low > 0.5 {
+                        let mid = (low + high) / 2.0;
+                        if fits_at(mid) {
+                            low = mid;
+                        } else {
+                            high = mid;
+                        }
+                    }
+                    low
+                },
+            }
+        };
+
+        // If not even the minimum size fits, drop lines until the text does,
+        // as if max_lines had been set accordingly. We can't go below one line.
+        let mut max_lines = self.max_lines;
+        if !fits_at(size) {
+            let mut lines = line_count(&self.lay_out_str(surface, suggested_bounds, &self.text, size));
+            if let Some(max_lines) = self.max_lines {
+                lines = cmp::min(lines, max_lines);
+            }
+            while lines > 1 {
+                lines -= 1;
+                max_lines = Some(lines);
+                if fits(&self.glyph_positions_with(surface, suggested_bounds, size, max_lines)) {
+                    break;
+                }
+            }
+        }
+
+        *self.fitted_size.borrow_mut() = Some(FittedSize {
+            width: available.width,
+            height: available.height,
+            fit,
+            size,
+            max_lines,
+        });
+        (size, max_lines)
+    }
+
+    /// Lay out the text at the given size, truncated to `max_lines`.
+    fn glyph_positions_with(&self, surface: &Surface, suggested_bounds: Bounds, size: f32, max_lines: Option<usize>) -> Vec<GlyphPosition> {
+        let mut layout = self.lay_out_str(surface, suggested_bounds, &self.text, size);
+        if let Some(max_lines) = max_lines {
+            if line_count(&layout) > max_lines {
+                let truncated = self.truncate(surface, suggested_bounds, size, max_lines);
+                layout = self.lay_out_str(surface, suggested_bounds, &truncated, size);
+            }
+        }
+
+        let mut glyphs = layout.glyphs().clone();
+        let lines = line_ranges(&layout);
+
+        // fontdue doesn't know about letter spacing, so spread out the glyphs
+        // of every line ourselves.
+        if self.letter_spacing != 0.0 {
+            for &(start, end) in &lines {
+                for (i, glyph) in glyphs[start..=end].iter_mut().enumerate() {
+                    glyph.x += (i as f32 * self.letter_spacing).round();
+                }
+            }
+        }
+
+        align_lines(&mut glyphs, &lines, self.alignment);
+        glyphs
+    }
+}
+
+/// The first font of the fallback chain (`font`, `fallbacks`, then the
+/// surface's [Surface::fallback_fonts]) that has a glyph for `c`. Fallback
+/// fonts are only loaded once they're needed.
+fn font_for(surface: &Surface, font: &str, fallbacks: &[String], c: char) -> usize {
+    let primary = surface.fonts.index(font);
+    let chain = fallbacks.iter().chain(surface.fallback_fonts.iter());
+    std::iter::once(primary)
+        .chain(chain.map(|name| surface.fonts.index(name)))
+        .find(|&font| surface.fonts.font(font).lookup_glyph_index(c) != 0)
+        .unwrap_or(primary)
+}
+
+/// Split `text` into runs that can each be laid out with a single font, see
+/// [font_for].
+fn font_runs<'a>(surface: &Surface, text: &'a str, font: &str, fallbacks: &[String]) -> Vec<(&'a str, usize)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut current = surface.fonts.index(font);
+    for (i, c) in text.char_indices() {
+        // Spaces and line breaks stick with the font of the text around them
+        // instead of starting a run of their own.
+        if c.is_whitespace() || c.is_control() {
+            continue;
+        }
+        let font = font_for(surface, font, fallbacks, c);
+        if font != current {
+            if i > start {
+                runs.push((&text[start..i], current));
+            }
+            start = i;
+            current = font;
+        }
+    }
+    if start < text.len() {
+        runs.push((&text[start..], current));
+    }
+    runs
+}
+
+/// The first and last glyph index of every line in `layout`.
+fn line_ranges<U: Copy>(layout: &Layout<U>) -> Vec<(usize, usize)> {
+    match layout.lines() {
+        Some(lines) => lines.iter().map(|l| (l.glyph_start, l.glyph_end)).collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Align the `lines` of `glyphs` relative to the widest line.
+fn align_lines<U: Copy>(glyphs: &mut [GlyphPosition<U>], lines: &[(usize, usize)], alignment: TextAlignment) {
+    if alignment == TextAlignment::Left {
+        return;
+    }
+
+    let widths: Vec<f32> = lines.iter()
+        .map(|&(start, end)| line_width(&glyphs[start..=end]))
+        .collect();
+    let block_width = widths.iter().copied().fold(0.0, f32::max);
+
+    for (i, (&(start, end), width)) in lines.iter().zip(widths).enumerate() {
+        let slack = block_width - width;
+        let line = &mut glyphs[start..=end];
+        match alignment {
+            TextAlignment::Left => {},
+            TextAlignment::Center => line.iter_mut().for_each(|g| g.x += (slack / 2.0).floor()),
+            TextAlignment::Right => line.iter_mut().for_each(|g| g.x += slack),
+            TextAlignment::Justify => {
+                let ends_paragraph = i == lines.len() - 1 || line[line.len() - 1].parent == '\n';
+                if !ends_paragraph {
+                    justify_line(line, slack);
+                }
+            },
+        }
+    }
+}
+
+/// The extent of laid out glyphs on the X and Y axes.
+fn glyph_extent<U: Copy>(glyphs: &[GlyphPosition<U>]) -> (u32, u32) {
+    let mut max_x: u32 = 0;
+    let mut max_y: u32 = 0;
+    for glyph in glyphs {
+        // Whitespace has no ink, but its position moves around with the
+        // alignment of the line.
+        if glyph.char_data.is_whitespace() {
+            continue;
+        }
+        let right_edge = glyph.x as u32 + glyph.width as u32;
+        let bottom_edge = glyph.y as u32 + glyph.height as u32;
+        if right_edge > max_x {
+            max_x = right_edge;
+        }
+        if bottom_edge > max_y {
+            max_y = bottom_edge;
+        }
+    }
+    (max_x, max_y)
+}
+
+fn line_count<U: Copy>(layout: &Layout<U>) -> usize {
+    layout.lines().map_or(0, |lines| lines.len())
+}
+
+/// The width of a laid out line, ignoring trailing whitespace.
+fn line_width<U: Copy>(line: &[GlyphPosition<U>]) -> f32 {
+    line.iter()
+        .filter(|g| !g.char_data.is_whitespace())
+        .map(|g| g.x + g.width as f32)
+        .fold(0.0, f32::max)
+}
+
+/// Distribute `slack` pixels evenly over the spaces between the words of
+/// `line`.
+fn justify_line<U: Copy>(line: &mut [GlyphPosition<U>], slack: f32) {
+    let Some(last_word) = line.iter().rposition(|g| !g.char_data.is_whitespace()) else {
+        return;
+    };
+    let gaps = line[..last_word].iter().filter(|g| g.char_data.is_whitespace()).count();
+    if gaps == 0 {
+        return;
+    }
+
+    let per_gap = slack / gaps as f32;
+    let mut gaps_seen = 0;
+    for glyph in line[..=last_word].iter_mut() {
+        if glyph.char_data.is_whitespace() {
+            gaps_seen += 1;
+        }
+        glyph.x += (gaps_seen as f32 * per_gap).round();
+    }
+}
+
+impl View for Text {
+    fn bounds(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let (mut max_x, mut max_y) = glyph_extent(&self.glyph_positions(surface, suggested_bounds));
+
+        // Now add padding.
+        max_x += self.padding_data().left + self.padding_data().right;
+        max_y += self.padding_data().top + self.padding_data().bottom;
+
+        Bounds::new(max_x, max_y)
+    }
+
+    fn draw(&self, surface: &mut Surface, origin_x: u32, origin_y: u32, suggested_bounds: Bounds) {
+        let glyphs = self.glyph_positions(surface, suggested_bounds);
+
+        let pad_origin_x = origin_x + self.padding_data().left;
+        let pad_origin_y = origin_y + self.padding_data().top;
+
+        let rendering = self.rendering.unwrap_or(surface.text_rendering);
+        let color = surface.rgb(self.color);
+        paint_glyphs(surface, &glyphs, pad_origin_x, pad_origin_y, rendering, |_| color);
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// Rasterize and draw laid out glyphs, relative to (origin_x, origin_y).
+/// `color` picks the color of every glyph.
+fn paint_glyphs<U: Copy>(
+    surface: &mut Surface,
+    glyphs: &[GlyphPosition<U>],
+    origin_x: u32,
+    origin_y: u32,
+    rendering: TextRendering,
+    color: impl Fn(&GlyphPosition<U>) -> image::Rgb<u8>,
+) {
+    for glyph in glyphs {
+        let (metrics, bitmap) = surface.fonts
+            .font(glyph.font_index)
+            .rasterize_indexed(glyph.key.glyph_index, glyph.key.px);
+        let color = color(glyph);
+
+        for y in 0..metrics.height {
+            for x in 0..metrics.width {
+                let coverage = bitmap[y * metrics.width + x];
+                let pixel_x = glyph.x as u32 + x as u32 + origin_x;
+                let pixel_y = glyph.y as u32 + y as u32 + origin_y;
+                match rendering {
+                    TextRendering::Threshold => {
+                        if coverage > TEXT_COVERAGE_THRESHOLD {
+                            surface.set_pixel(pixel_x, pixel_y, color);
+                        }
+                    },
+                    TextRendering::Grayscale => {
+                        surface.blend_pixel(pixel_x, pixel_y, color, coverage);
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// A piece of a [RichText] with its own style. Everything that's `None` is
+/// taken from the [RichText].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Span {
+    pub text: String,
+    /// The name of the font to use for this span.
+    pub font: Option<String>,
+    /// The font size to use for this span.
+    pub size: Option<f32>,
+    /// The color of this span.
+    pub color: Option<Color>,
+}
+
+impl Span {
+    /// Create a span that uses the style of its [RichText].
+    pub fn new(text: &str) -> Span {
+        Span {
+            text: text.to_string(),
+            font: None,
+            size: None,
+            color: None,
+        }
+    }
+}
+
+/// Renders a paragraph made up of differently styled [Span]s, e.g. a bold
+/// label followed by regular text:
+///
+/// ```
+/// let mut label = Span::new("Bahn: ");
+/// label.font = Some(String::from("Roboto-Bold"));
+/// let mut delay = Span::new("5 min late");
+/// delay.color = Some(Color::Accent);
+/// let text = RichText::new(vec![label, delay], 13.0, "Roboto");
+/// ```
+///
+/// The spans are laid out as one block: lines wrap across span boundaries, and
+/// lines containing bigger text get taller. Wrapping, alignment, line height,
+/// and font fallback work just like they do for [Text].
+pub struct RichText {
+    pub spans: Vec<Span>,
+
+    /// The font size for spans that don't have their own.
+    pub size: f32,
+
+    /// The font for spans that don't have their own.
+    pub font: String,
+
+    /// The color for spans that don't have their own. Defaults to
+    /// [Color::Black].
+    pub color: Color,
+
+    /// Fonts to use for characters that the font of a span doesn't have, see
+    /// [Text::fallback_fonts].
+    pub fallback_fonts: Vec<String>,
+
+    /// Wrap the text to the width of the suggested bounds, see
+    /// [Text::wrap_text].
+    pub wrap_text: bool,
+
+    /// How lines are aligned with each other. Defaults to
+    /// [TextAlignment::Left].
+    pub alignment: TextAlignment,
+
+    /// The distance between lines as a multiple of the line height. Defaults
+    /// to 1.0.
+    pub line_height: f32,
+
+    /// How to turn the glyph outlines into pixels. If this is `None`, the
+    /// text uses the surface's default ([Surface::text_rendering]).
+    pub rendering: Option<TextRendering>,
+
+    padding: Padding,
+}
+
+impl RichText {
+    pub fn new(spans: Vec<Span>, size: f32, font: &str) -> RichText {
+        RichText {
+            spans,
+            size,
+            font: font.to_string(),
+            color: Color::Black,
+            fallback_fonts: Vec::new(),
+            wrap_text: false,
+            alignment: TextAlignment::Left,
+            line_height: 1.0,
+            rendering: None,
+            padding: Padding::zero(),
+        }
+    }
+
+    /// Lay out all spans and return the positioned glyphs, relative to the
+    /// padded origin. The user data of every glyph is the index of its span.
+    fn glyph_positions(&self, surface: &Surface, suggested_bounds: Bounds) -> Vec<GlyphPosition<usize>> {
+        let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+        let mut settings = *layout.settings();
+        if self.wrap_text {
+            settings.max_width = Some((suggested_bounds - self.padding_data().bounds()).width as f32);
+        }
+        settings.line_height = self.line_height;
+        layout.reset(&settings);
+
+        for (i, span) in self.spans.iter().enumerate() {
+            let font = span.font.as_deref().unwrap_or(&self.font);
+            let size = span.size.unwrap_or(self.size);
+            for (run, font) in font_runs(surface, &span.text, font, &self.fallback_fonts) {
+                layout.append(&surface.fonts.fonts(), &TextStyle::with_user_data(run, size, font, i));
+            }
+        }
+
+        let mut glyphs = layout.glyphs().clone();
+        align_lines(&mut glyphs, &line_ranges(&layout), self.alignment);
+        glyphs
+    }
+}
+
+impl View for RichText {
+    fn bounds(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let (width, height) = glyph_extent(&self.glyph_positions(surface, suggested_bounds));
+        Bounds::new(width, height) + self.padding_data().bounds()
+    }
+
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let glyphs = self.glyph_positions(surface, suggested_bounds);
+        let rendering = self.rendering.unwrap_or(surface.text_rendering);
+        let colors: Vec<image::Rgb<u8>> = self.spans
+            .iter()
+            .map(|span| surface.rgb(span.color.unwrap_or(self.color)))
+            .collect();
+
+        paint_glyphs(
+            surface,
+            &glyphs,
+            x + self.padding_data().left,
+            y + self.padding_data().top,
+            rendering,
+            |glyph| colors[glyph.user_data],
+        );
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// Blend `pixels` onto the surface with their top left corner at (x, y). With a
+/// `tint`, dark pixels are drawn in the tint color and light ones are skipped.
+fn paint_pixels(surface: &mut Surface, x: u32, y: u32, pixels: &RgbaImage, tint: Option<Color>) {
+    let tint = tint.map(|c| surface.rgb(c));
+
+    for (img_x, img_y, pixel) in pixels.enumerate_pixels() {
+        let alpha = pixel.0[3];
+        let (color, coverage) = match tint {
+            Some(color) => {
+                // Use the darkness of the pixel as the coverage for the tint
+                // color.
+                let darkness = 255 - pixel.to_luma().0[0];
+                (color, (darkness as u32 * alpha as u32 / 255) as u8)
+            },
+            None => (pixel.to_rgb(), alpha),
+        };
+        surface.blend_pixel(x + img_x, y + img_y, color, coverage);
+    }
+}
+
+/// How an [Image] or [SvgImage] fills the space it's given.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContentMode {
+    /// Always draw the image at its native size, no matter how much space it's
+    /// offered.
+    Native,
+    /// Scale the image up or down, keeping its aspect ratio, so that it fits
+    /// entirely into the space it's offered.
+    Fit,
+    /// Scale the image up or down, keeping its aspect ratio, so that it covers
+    /// the entire space it's offered. Whatever sticks out is cropped, keeping
+    /// the image centered.
+    Fill,
+    /// Scale the image to exactly the space it's offered, distorting it if the
+    /// aspect ratios don't match.
+    Stretch,
+}
+
+/// Renders an image.
+///
+/// By default, an image is drawn at its native size ([ContentMode::Native]).
+/// Set [content_mode] to have it scale to the space it's offered instead. In
+/// that case, the image is flexible: when zero-hinted, it collapses to nothing,
+/// otherwise it takes up the suggested space (see [ContentMode] for the
+/// details). The [filter] determines how the image is resampled.
+///
+/// Images keep their alpha channel and are blended onto whatever is already on
+/// the [Surface], so transparent logos don't end up in a box.
+pub struct Image {
+    image_data: RgbaImage,
+    padding: Padding,
+
+    /// Draw the image as a single-color mask instead of using its own colors.
+    /// Dark pixels of the image are drawn in the given [Color], light pixels
+    /// leave the surface untouched. This is handy for drawing monochrome logos
+    /// and icons in the accent color of the panel.
+    pub tint: Option<Color>,
+
+    /// How the image fills the space it's given. Defaults to
+    /// [ContentMode::Native].
+    pub content_mode: ContentMode,
+
+    /// The filter used for scaling the image. Defaults to
+    /// [FilterType::CatmullRom].
+    pub filter: FilterType,
+}
+
+impl Image {
+    /// Create a new image from the given pixels.
+    pub fn new(image_data: RgbaImage) -> Image {
+        Image {
+            image_data,
+            padding: Padding::zero(),
+            tint: None,
+            content_mode: ContentMode::Native,
+            filter: FilterType::CatmullRom,
+        }
+    }
+
+    /// Create a new image from the given reader ([std::io::Read]). The format
+    /// is detected from the data itself: PNG, JPEG, GIF (only the first frame),
+    /// BMP, and WebP are supported.
+    pub fn from_data<R: Read + Seek>(data: R) -> Result<Image> {
+        let reader = image::ImageReader::new(BufReader::new(data))
+            .with_guessed_format()
+            .with_context(|| "Error reading image.")?;
+        match reader.format() {
+            Some(ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif | ImageFormat::Bmp | ImageFormat::WebP) => {},
+            Some(format) => bail!("Unsupported image format {:?}.", format),
+            None => bail!("Unknown image format."),
+        }
+        let img = reader
+            .decode()
+            .with_context(|| "Error decoding image.")?;
+
+        Ok(Image::new(img.into_rgba8()))
+    }
+
+    /// The size of the image (without padding) when it's offered `available`
+    /// space.
+    fn scaled_size(&self, available: Bounds) -> Bounds {
+        let (width, height) = self.image_data.dimensions();
+        if self.content_mode == ContentMode::Native {
+            return Bounds::new(width, height);
+        }
+        if available.hint == SizingHint::ZeroSpace || width == 0 || height == 0 {
+            return Bounds::new(0, 0);
+        }
+
+        match self.content_mode {
+            ContentMode::Fit => {
+                let scale = f64::min(
+                    available.width as f64 / width as f64,
+                    available.height as f64 / height as f64,
+                );
+                Bounds::new(
+                    cmp::min((width as f64 * scale).round() as u32, available.width),
+                    cmp::min((height as f64 * scale).round() as u32, available.height),
+                )
+            },
+            _ => Bounds::new(available.width, available.height),
+        }
+    }
+
+    /// Produce the pixels to draw for the given size.
+    fn render(&self, size: Bounds) -> RgbaImage {
+        let (width, height) = self.image_data.dimensions();
+        if size.width == width && size.height == height {
+            return self.image_data.clone();
+        }
+        if size.width == 0 || size.height == 0 {
+            return RgbaImage::new(0, 0);
+        }
+
+        if self.content_mode == ContentMode::Fill {
+            // Scale so the image covers the entire area, then cut out the
+            // center.
+            let scale = f64::max(
+                size.width as f64 / width as f64,
+                size.height as f64 / height as f64,
+            );
+            let scaled_width = cmp::max((width as f64 * scale).ceil() as u32, size.width);
+            let scaled_height = cmp::max((height as f64 * scale).ceil() as u32, size.height);
+            let scaled = imageops::resize(&self.image_data, scaled_width, scaled_height, self.filter);
+            imageops::crop_imm(
+                &scaled,
+                (scaled_width - size.width) / 2,
+                (scaled_height - size.height) / 2,
+                size.width,
+                size.height,
+            ).to_image()
+        } else {
+            imageops::resize(&self.image_data, size.width, size.height, self.filter)
+        }
+    }
+}
+
+impl View for Image {
+    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        self.scaled_size(suggested_bounds - self.padding_data().bounds()) +
+            self.padding_data().bounds()
+    }
+
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let pad_origin_x = x + self.padding_data().left;
+        let pad_origin_y = y + self.padding_data().top;
+
+        let pixels = self.render(self.scaled_size(suggested_bounds - self.padding_data().bounds()));
+        paint_pixels(surface, pad_origin_x, pad_origin_y, &pixels, self.tint);
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// Renders an SVG.
+///
+/// Unlike [Image], which resamples pixels, the SVG is rasterized at exactly the
+/// size it ends up with, so the same file looks crisp on every tag size. By
+/// default, it's scaled to fit the space it's offered ([ContentMode::Fit]),
+/// which makes it flexible in the same way as a scaled [Image]. With
+/// [ContentMode::Native], it's drawn at the size given in the SVG itself.
+///
+/// Text inside the SVG isn't rendered, convert it to paths before using the
+/// file here.
+pub struct SvgImage {
+    tree: resvg::usvg::Tree,
+    padding: Padding,
+
+    /// Draw the SVG as a single-color mask instead of using its own colors.
+    /// See [Image::tint].
+    pub tint: Option<Color>,
+
+    /// How the SVG fills the space it's given. Defaults to [ContentMode::Fit].
+    pub content_mode: ContentMode,
+}
+
+impl SvgImage {
+    /// Create a new SVG image from the given reader ([std::io::Read]).
+    pub fn from_data<R: Read>(mut data: R) -> Result<SvgImage> {
+        let mut svg = Vec::new();
+        data.read_to_end(&mut svg)
+            .with_context(|| "Error reading SVG.")?;
+        let tree = resvg::usvg::Tree::from_data(&svg, &resvg::usvg::Options::default())
+            .with_context(|| "Error parsing SVG.")?;
+
+        Ok(SvgImage {
+            tree,
+            padding: Padding::zero(),
+            tint: None,
+            content_mode: ContentMode::Fit,
+        })
+    }
+
+    /// The size of the SVG as given in the file itself.
+    fn native_size(&self) -> (f32, f32) {
+        let size = self.tree.size();
+        (size.width(), size.height())
+    }
+
+    /// The size of the SVG (without padding) when it's offered `available`
+    /// space.
+    fn scaled_size(&self, available: Bounds) -> Bounds {
+        let (width, height) = self.native_size();
+        if self.content_mode == ContentMode::Native {
+            return Bounds::new(width.ceil() as u32, height.ceil() as u32);
+        }
+        if available.hint == SizingHint::ZeroSpace {
+            return Bounds::new(0, 0);
+        }
+
+        match self.content_mode {
+            ContentMode::Fit => {
+                let scale = f32::min(
+                    available.width as f32 / width,
+                    available.height as f32 / height,
+                );
+                Bounds::new(
+                    cmp::min((width * scale).round() as u32, available.width),
+                    cmp::min((height * scale).round() as u32, available.height),
+                )
+            },
+            _ => Bounds::new(available.width, available.height),
+        }
+    }
+
+    /// Rasterize the SVG at the given size.
+    fn render(&self, size: Bounds) -> RgbaImage {
+        let Some(mut pixmap) = resvg::tiny_skia::Pixmap::new(size.width, size.height) else {
+            return RgbaImage::new(0, 0);
+        };
+
+        let (width, height) = self.native_size();
+        let scale_x = size.width as f32 / width;
+        let scale_y = size.height as f32 / height;
+        let transform = match self.content_mode {
+            ContentMode::Native | ContentMode::Stretch => {
+                resvg::tiny_skia::Transform::from_scale(scale_x, scale_y)
+            },
+            ContentMode::Fit | ContentMode::Fill => {
+                // Keep the aspect ratio and center the drawing. For Fill, the
+                // parts sticking out simply end up outside of the pixmap.
+                let scale = if self.content_mode == ContentMode::Fit {
+                    f32::min(scale_x, scale_y)
+                } else {
+                    f32::max(scale_x, scale_y)
+                };
+                resvg::tiny_skia::Transform::from_row(
+                    scale, 0.0, 0.0, scale,
+                    (size.width as f32 - width * scale) / 2.0,
+                    (size.height as f32 - height * scale) / 2.0,
+                )
+            },
+        };
+        resvg::render(&self.tree, transform, &mut pixmap.as_mut());
+
+        // tiny-skia works with premultiplied alpha, the rest of the drawing
+        // code doesn't.
+        RgbaImage::from_fn(size.width, size.height, |x, y| {
+            let pixel = pixmap.pixel(x, y).unwrap().demultiply();
+            image::Rgba([pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()])
+        })
+    }
+}
+
+impl View for SvgImage {
+    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        self.scaled_size(suggested_bounds - self.padding_data().bounds()) +
+            self.padding_data().bounds()
+    }
+
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let pad_origin_x = x + self.padding_data().left;
+        let pad_origin_y = y + self.padding_data().top;
+
+        let pixels = self.render(self.scaled_size(suggested_bounds - self.padding_data().bounds()));
+        paint_pixels(surface, pad_origin_x, pad_origin_y, &pixels, self.tint);
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// How much of a [QrCode] can be damaged or covered while still being
+/// readable. Higher levels need more modules for the same data, so the code
+/// gets denser.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ErrorCorrection {
+    /// Recovers about 7% of the code.
+    Low,
+    /// Recovers about 15% of the code.
+    #[default]
+    Medium,
+    /// Recovers about 25% of the code.
+    Quartile,
+    /// Recovers about 30% of the code.
+    High,
+}
+
+impl ErrorCorrection {
+    fn ec_level(&self) -> qrcode::EcLevel {
+        match self {
+            ErrorCorrection::Low => qrcode::EcLevel::L,
+            ErrorCorrection::Medium => qrcode::EcLevel::M,
+            ErrorCorrection::Quartile => qrcode::EcLevel::Q,
+            ErrorCorrection::High => qrcode::EcLevel::H,
+        }
+    }
+}
+
+/// A QR code, e.g. linking to an article or for joining a WiFi network.
+///
+/// Every module (the "pixels" of the code) is drawn as a square of exactly
+/// `scale` x `scale` pixels, so the code stays crisp on the panel. Unless the
+/// scale is fixed, the QR code picks the largest integer scale at which it fits
+/// the space it's offered, but never goes below one pixel per module. Zero-hinted,
+/// it reports its size at scale one, which makes it willing to flex down in a
+/// stack, but not to disappear.
+///
+/// The quiet zone -- the light border scanners need to find the code -- is part
+/// of the view and is always painted white. It comes on top of the view's
+/// [Padding].
+pub struct QrCode {
+    /// The width of the code in modules, without the quiet zone.
+    width: u32,
+    /// Whether each module is dark, row by row.
+    modules: Vec<bool>,
+    padding: Padding,
+
+    /// The width of the quiet zone in modules. Defaults to 4, as required by the
+    /// QR code specification. Some scanners cope with less.
+    pub quiet_zone: u32,
+
+    /// A fixed number of pixels per module, or `None` to fit the suggested
+    /// bounds. Defaults to `None`.
+    pub scale: Option<u32>,
+
+    /// The color of the dark modules. Defaults to black.
+    pub color: Color,
+}
+
+impl QrCode {
+    /// Encode `data` as a QR code with the given error correction level. Fails
+    /// if the data is too long to fit into a QR code at that level.
+    pub fn new(data: &str, error_correction: ErrorCorrection) -> Result<QrCode> {
+        let code = qrcode::QrCode::with_error_correction_level(data, error_correction.ec_level())
+            .with_context(|| format!("Could not encode {:?} as a QR code", data))?;
+        let modules = code.to_colors()
+            .into_iter()
+            .map(|c| c == qrcode::Color::Dark)
+            .collect();
+
+        Ok(QrCode {
+            width: code.width() as u32,
+            modules,
+            padding: Padding::zero(),
+            quiet_zone: 4,
+            scale: None,
+            color: Color::Black,
+        })
+    }
+
+    /// The width of the code in modules, including the quiet zone.
+    fn total_modules(&self) -> u32 {
+        self.width + 2 * self.quiet_zone
+    }
+
+    /// The number of pixels per module when the code is offered `available`
+    /// space (without padding).
+    fn module_scale(&self, available: Bounds) -> u32 {
+        if let Some(scale) = self.scale {
+            return scale;
+        }
+        if available.hint == SizingHint::ZeroSpace {
+            return 1;
+        }
+        cmp::max(1, cmp::min(available.width, available.height) / self.total_modules())
+    }
+}
+
+impl View for QrCode {
+    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let scale = self.module_scale(suggested_bounds - self.padding_data().bounds());
+        let size = self.total_modules() * scale;
+        Bounds::new(size, size) + self.padding_data().bounds()
+    }
+
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let scale = self.module_scale(suggested_bounds - self.padding_data().bounds());
+        let size = self.total_modules() * scale;
+        let origin_x = x + self.padding_data().left;
+        let origin_y = y + self.padding_data().top;
+        let dark = surface.rgb(self.color);
+        let light = surface.rgb(Color::White);
+
+        for py in 0..size {
+            for px in 0..size {
+                let module_x = (px / scale) as i64 - self.quiet_zone as i64;
+                let module_y = (py / scale) as i64 - self.quiet_zone as i64;
+                let in_code = (0..self.width as i64).contains(&module_x) &&
+                    (0..self.width as i64).contains(&module_y);
+                let is_dark = in_code &&
+                    self.modules[(module_y * self.width as i64 + module_x) as usize];
+                surface.set_pixel(origin_x + px, origin_y + py, if is_dark { dark } else { light });
+            }
+        }
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// The space between the bars of a [Barcode] and its human-readable text.
+const BARCODE_TEXT_SPACING: u32 = 2;
+
+/// A one-dimensional barcode, e.g. an EAN-13 product code. See [Symbology] for
+/// the supported formats.
+///
+/// Scanners need every module (the narrowest bar or space) to be equally wide,
+/// so modules are always a whole number of pixels wide. Unless the module width
+/// is fixed, the barcode picks the largest width at which it fits the space
+/// it's offered, but never less than one pixel. The bars are flexible in
+/// height, like a [Spacer], unless [bar_height] is set.
+///
+/// The quiet zone the symbology requires is part of the view and is painted
+/// white. The human-readable text is centered underneath the bars.
+pub struct Barcode {
+    code: barcode::Encoded,
+    padding: Padding,
+
+    /// The width of a module in pixels, or `None` to fit the suggested bounds.
+    /// Defaults to `None`.
+    pub module_width: Option<u32>,
+
+    /// The height of the bars in pixels, or `None` to take up all the height
+    /// that isn't needed for the text. Defaults to `None`.
+    pub bar_height: Option<u32>,
+
+    /// The human-readable text underneath the bars, or `None` to leave it out.
+    /// Defaults to the encoded data (including the check digit) in the default
+    /// font at 12 pixels. Change the view to change its font, size, or color.
+    pub text: Option<Text>,
+
+    /// The color of the bars. Defaults to black.
+    pub color: Color,
+}
+
+impl Barcode {
+    /// Encode `data` using the given symbology. Fails if the symbology can't
+    /// encode the data, e.g. letters in an EAN-13 or a wrong check digit.
+    pub fn new(symbology: Symbology, data: &str) -> Result<Barcode> {
+        let code = barcode::encode(symbology, data)
+            .with_context(|| format!("Could not encode {:?} as a {:?} barcode", data, symbology))?;
+        let text = Text::new(code.text.clone(), 12.0, DEFAULT_FONT);
+
+        Ok(Barcode {
+            code,
+            padding: Padding::zero(),
+            module_width: None,
+            bar_height: None,
+            text: Some(text),
+            color: Color::Black,
+        })
+    }
+
+    /// The width of the barcode in modules, including the quiet zones.
+    fn total_modules(&self) -> u32 {
+        self.code.modules.len() as u32 + 2 * self.code.quiet_zone
+    }
+
+    /// The width of a module when the barcode is offered `available` space
+    /// (without padding).
+    fn module_width(&self, available: Bounds) -> u32 {
+        if let Some(width) = self.module_width {
+            return width;
+        }
+        if available.hint == SizingHint::ZeroSpace {
+            return 1;
+        }
+        cmp::max(1, available.width / self.total_modules())
+    }
+
+    /// The height of the text including the spacing above it, and its bounds.
+    fn text_size(&self, surface: &Surface, width: u32) -> Option<(u32, Bounds)> {
+        self.text.as_ref().map(|text| {
+            let bounds = text.bounds(surface, Bounds::new(width, 999));
+            (bounds.height + BARCODE_TEXT_SPACING, bounds)
+        })
+    }
+
+    /// The size of the bars and the text (without padding) for the given space.
+    fn measure(&self, surface: &Surface, available: Bounds) -> (Bounds, Option<Bounds>) {
+        let width = self.total_modules() * self.module_width(available);
+        let text = self.text_size(surface, width);
+        let text_height = text.map(|t| t.0).unwrap_or(0);
+        let bar_height = flexible_length(
+            self.bar_height,
+            available.height.saturating_sub(text_height),
+            available.hint,
+        );
+        (Bounds::new(width, bar_height), text.map(|t| t.1))
+    }
+}
+
+impl View for Barcode {
+    fn bounds(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let (bars, text) = self.measure(surface, suggested_bounds - self.padding_data().bounds());
+        let text_height = text.map(|t| t.height + BARCODE_TEXT_SPACING).unwrap_or(0);
+        let width = cmp::max(bars.width, text.map(|t| t.width).unwrap_or(0));
+        Bounds::new(width, bars.height + text_height) + self.padding_data().bounds()
+    }
+
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let (bars, text_bounds) = self.measure(surface, suggested_bounds - self.padding_data().bounds());
+        let module_width = bars.width / self.total_modules();
+        let origin_x = x + self.padding_data().left;
+        let origin_y = y + self.padding_data().top;
+        let dark = surface.rgb(self.color);
+        let light = surface.rgb(Color::White);
+
+        for px in 0..bars.width {
+            let module = (px / module_width) as i64 - self.code.quiet_zone as i64;
+            let is_dark = usize::try_from(module)
+                .ok()
+                .and_then(|m| self.code.modules.get(m).copied())
+                .unwrap_or(false);
+            for py in 0..bars.height {
+                surface.set_pixel(origin_x + px, origin_y + py, if is_dark { dark } else { light });
+            }
+        }
+
+        if let (Some(text), Some(text_bounds)) = (&self.text, text_bounds) {
+            let text_x = origin_x + bars.width.saturating_sub(text_bounds.width) / 2;
+            let text_y = origin_y + bars.height + BARCODE_TEXT_SPACING;
+            surface.draw_view(text, text_x, text_y, text_bounds);
+        }
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// The range of values a chart maps onto its height.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ValueRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl ValueRange {
+    /// The smallest range that contains all finite `values` and the
+    /// `threshold`, and zero if `include_zero` is set.
+    fn covering(values: &[f32], threshold: Option<f32>, include_zero: bool) -> ValueRange {
+        let zero = if include_zero { Some(0.0) } else { None };
+        let all = values.iter().copied().chain(threshold).chain(zero).filter(|v| v.is_finite());
+        all.fold(None, |range: Option<ValueRange>, v| match range {
+            Some(r) => Some(ValueRange { min: r.min.min(v), max: r.max.max(v) }),
+            None => Some(ValueRange { min: v, max: v }),
+        }).unwrap_or(ValueRange { min: 0.0, max: 0.0 })
+    }
+}
+
+/// The diameter of the markers charts put on their minimum and maximum.
+const CHART_MARKER_SIZE: u32 = 5;
+
+/// The space between a chart's axes and their labels.
+const CHART_LABEL_SPACING: u32 = 2;
+
+/// The area of a chart the data is drawn into, and the values it covers.
+struct Plot {
+    x: i64,
+    y: i64,
+    width: u32,
+    height: u32,
+    range: ValueRange,
+}
+
+impl Plot {
+    /// The vertical position of `value`. If all values are the same, they're
+    /// drawn in the middle.
+    fn y_for(&self, value: f32) -> i64 {
+        let span = self.range.max - self.range.min;
+        if span <= 0.0 {
+            return self.y + (self.height / 2) as i64;
+        }
+        let offset = (self.range.max - value) / span * self.height.saturating_sub(1) as f32;
+        self.y + offset.round() as i64
+    }
+
+    /// The horizontal position of the `index`th of `count` points, spread out
+    /// evenly across the plot.
+    fn x_for(&self, index: usize, count: usize) -> i64 {
+        if count <= 1 {
+            return self.x + (self.width / 2) as i64;
+        }
+        let offset = index as f32 * self.width.saturating_sub(1) as f32 / (count - 1) as f32;
+        self.x + offset.round() as i64
+    }
+}
+
+/// The indexes of the smallest and the largest finite value.
+fn min_max_indexes(values: &[f32]) -> Option<(usize, usize)> {
+    let finite = || values.iter().enumerate().filter(|(_, v)| v.is_finite());
+    let min = finite().min_by(|a, b| a.1.total_cmp(b.1))?.0;
+    let max = finite().max_by(|a, b| a.1.total_cmp(b.1))?.0;
+    Some((min, max))
+}
+
+/// Paint a marker centered on the given point: filled for the maximum,
+/// hollow for the minimum.
+fn paint_marker(surface: &mut Surface, (x, y): (i64, i64), filled: bool, color: Color) {
+    let radius = (CHART_MARKER_SIZE / 2) as i64;
+    if x < radius || y < radius {
+        return;
+    }
+    let style = ShapeStyle {
+        color,
+        stroke_width: 1,
+        fill: Some(if filled { color } else { Color::White }),
+    };
+    paint_rounded_rect(
+        surface,
+        (x - radius) as u32,
+        (y - radius) as u32,
+        CHART_MARKER_SIZE,
+        CHART_MARKER_SIZE,
+        CHART_MARKER_SIZE / 2,
+        style,
+    );
+}
+
+/// Paint a dashed horizontal line across the plot in the accent color. It's
+/// dashed so it can be told apart from the data on panels where the accent
+/// color is black.
+fn paint_threshold(surface: &mut Surface, plot: &Plot, threshold: f32) {
+    let y = plot.y_for(threshold);
+    if y < 0 {
+        return;
+    }
+    let color = surface.rgb(Color::Accent);
+    for offset in (0..plot.width).filter(|offset| offset % 6 < 4) {
+        let x = plot.x + offset as i64;
+        if x >= 0 {
+            surface.set_pixel(x as u32, y as u32, color);
+        }
+    }
+}
+
+/// Paint the values as a polyline through the plot.
+fn paint_series(surface: &mut Surface, plot: &Plot, values: &[f32], stroke_width: u32, color: Color) {
+    let color = surface.rgb(color);
+    // paint_line extends the stroke to the right and bottom, so shift it to
+    // center it on the points.
+    let shift = (stroke_width / 2) as i64;
+    let points: Vec<(i64, i64)> = values.iter()
+        .enumerate()
+        .filter(|(_, v)| v.is_finite())
+        .map(|(i, &v)| (plot.x_for(i, values.len()) - shift, plot.y_for(v) - shift))
+        .collect();
+    if let [point] = points[..] {
+        paint_line(surface, point, point, stroke_width, color);
+    }
+    for segment in points.windows(2) {
+        paint_line(surface, segment[0], segment[1], stroke_width, color);
+    }
+}
+
+/// A label on one of a chart's axes, and its size.
+fn axis_label(surface: &Surface, text: String, font: &str, size: f32) -> (Text, Bounds) {
+    let mut label = Text::new(text, size, font);
+    label.max_lines = Some(1);
+    let bounds = label.bounds(surface, Bounds::new(999, 999));
+    (label, bounds)
+}
+
+/// The labels for the top and the bottom of the value axis.
+fn value_labels(surface: &Surface, range: ValueRange, decimals: usize, font: &str, size: f32) -> [(Text, Bounds); 2] {
+    [range.max, range.min].map(|value| axis_label(surface, format!("{:.*}", decimals, value), font, size))
+}
+
+/// Draw the axes of a [LineChart] or [BarChart] into a `size` area at
+/// `origin`: the value labels on the left, `x_label_height` pixels for the
+/// category labels at the bottom, and the axis lines between them and the
+/// plot. Returns the plot, which is inset by `inset` pixels so markers fit,
+/// and the vertical position of the category axis line.
+fn paint_axes(
+    surface: &mut Surface,
+    (x, y): (u32, u32),
+    size: Bounds,
+    range: ValueRange,
+    value_labels: Option<&[(Text, Bounds); 2]>,
+    x_label_height: u32,
+    inset: u32,
+) -> (Plot, u32) {
+    let gutter = value_labels
+        .map(|labels| labels.iter().map(|l| l.1.width).max().unwrap_or(0) + CHART_LABEL_SPACING)
+        .unwrap_or(0);
+    // One pixel for each axis line.
+    let plot_width = size.width.saturating_sub(gutter + 1 + 2 * inset);
+    let plot_height = size.height.saturating_sub(x_label_height + 1 + 2 * inset);
+    let axis_x = x + gutter;
+    let axis_y = y + size.height.saturating_sub(x_label_height + 1);
+    let plot = Plot {
+        x: (axis_x + 1 + inset) as i64,
+        y: (y + inset) as i64,
+        width: plot_width,
+        height: plot_height,
+        range,
+    };
+
+    let black = surface.rgb(Color::Black);
+    for line_y in y..=axis_y {
+        surface.set_pixel(axis_x, line_y, black);
+    }
+    for line_x in axis_x..x + size.width {
+        surface.set_pixel(line_x, axis_y, black);
+    }
+    if let Some([(max, max_bounds), (min, min_bounds)]) = value_labels {
+        let right = axis_x.saturating_sub(CHART_LABEL_SPACING);
+        surface.draw_view(max, right.saturating_sub(max_bounds.width), y, *max_bounds);
+        let min_y = (axis_y + 1).saturating_sub(min_bounds.height);
+        surface.draw_view(min, right.saturating_sub(min_bounds.width), min_y, *min_bounds);
+    }
+
+    (plot, axis_y)
+}
+
+/// A small line chart without axes or labels, meant to be put next to a number
+/// to show its trend.
+///
+/// Sparklines are flexible in both directions, just like a flexible
+/// [Rectangle], and scale the values to whatever size they end up with.
+pub struct Sparkline {
+    pub values: Vec<f32>,
+    /// The width of the sparkline, or `None` for a flexible width.
+    pub width: Option<u32>,
+    /// The height of the sparkline, or `None` for a flexible height.
+    pub height: Option<u32>,
+    /// The values at the top and bottom edge. Defaults to `None`, which uses
+    /// the smallest range containing all values and the threshold.
+    pub range: Option<ValueRange>,
+    /// Draw a dashed line at this value in the accent color. Defaults to
+    /// `None`.
+    pub threshold: Option<f32>,
+    /// Mark the smallest (hollow) and largest (filled) value. Defaults to
+    /// `true`.
+    pub show_min_max: bool,
+    /// The width of the line in pixels. Defaults to 1.
+    pub stroke_width: u32,
+    /// The color of the line and markers. Defaults to [Color::Black].
+    pub color: Color,
+    padding: Padding,
+}
+
+impl Sparkline {
+    /// Create a new flexible sparkline for the given values.
+    pub fn new(values: Vec<f32>) -> Self {
+        Sparkline {
+            values,
+            width: None,
+            height: None,
+            range: None,
+            threshold: None,
+            show_min_max: true,
+            stroke_width: 1,
+            color: Color::Black,
+            padding: Padding::zero(),
+        }
+    }
+}
+
+impl View for Sparkline {
+    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let available = suggested_bounds - self.padding_data().bounds();
+        Bounds::new(
+            flexible_length(self.width, available.width, suggested_bounds.hint),
+            flexible_length(self.height, available.height, suggested_bounds.hint),
+        ) + self.padding_data().bounds()
+    }
+
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let size = self.bounds(surface, suggested_bounds) - self.padding_data().bounds();
+        let inset = if self.show_min_max { CHART_MARKER_SIZE / 2 } else { 0 };
+        let plot = Plot {
+            x: (x + self.padding_data().left + inset) as i64,
+            y: (y + self.padding_data().top + inset) as i64,
+            width: size.width.saturating_sub(2 * inset),
+            height: size.height.saturating_sub(2 * inset),
+            range: self.range.unwrap_or(ValueRange::covering(&self.values, self.threshold, false)),
+        };
+        if plot.width == 0 || plot.height == 0 {
+            return;
+        }
+
+        if let Some(threshold) = self.threshold {
+            paint_threshold(surface, &plot, threshold);
+        }
+        paint_series(surface, &plot, &self.values, self.stroke_width, self.color);
+        if let Some((min, max)) = min_max_indexes(&self.values).filter(|_| self.show_min_max) {
+            let count = self.values.len();
+            paint_marker(surface, (plot.x_for(min, count), plot.y_for(self.values[min])), false, self.color);
+            paint_marker(surface, (plot.x_for(max, count), plot.y_for(self.values[max])), true, self.color);
+        }
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// A line chart with axes: the values are drawn as a line, with the top and
+/// bottom of the value range labeled on the left, and [labels] spread out
+/// evenly underneath.
+///
+/// Like a [Sparkline], line charts are flexible in both directions.
+pub struct LineChart {
+    pub values: Vec<f32>,
+    /// Labels for the horizontal axis, e.g. times of day. The first one goes
+    /// under the first value, the last one under the last value, the others
+    /// are spread out evenly in between. Empty by default.
+    pub labels: Vec<String>,
+    /// The width of the chart, or `None` for a flexible width.
+    pub width: Option<u32>,
+    /// The height of the chart, or `None` for a flexible height.
+    pub height: Option<u32>,
+    /// The values at the top and bottom of the value axis. Defaults to `None`,
+    /// which uses the smallest range containing all values and the threshold.
+    pub range: Option<ValueRange>,
+    /// Draw a dashed line at this value in the accent color. Defaults to
+    /// `None`.
+    pub threshold: Option<f32>,
+    /// Mark the smallest (hollow) and largest (filled) value. Defaults to
+    /// `true`.
+    pub show_min_max: bool,
+    /// Label the top and bottom of the value axis. Defaults to `true`.
+    pub value_labels: bool,
+    /// The number of decimals in the value labels. Defaults to 0.
+    pub decimals: usize,
+    /// The font of the labels. Defaults to [DEFAULT_FONT].
+    pub font: String,
+    /// The size of the labels. Defaults to 10.
+    pub font_size: f32,
+    /// The width of the line in pixels. Defaults to 1.
+    pub stroke_width: u32,
+    /// The color of the line and markers. Defaults to [Color::Black].
+    pub color: Color,
+    padding: Padding,
+}
+
+impl LineChart {
+    /// Create a new flexible line chart for the given values.
+    pub fn new(values: Vec<f32>) -> Self {
+        LineChart {
+            values,
+            labels: Vec::new(),
+            width: None,
+            height: None,
+            range: None,
+            threshold: None,
+            show_min_max: true,
+            value_labels: true,
+            decimals: 0,
+            font: DEFAULT_FONT.to_string(),
+            font_size: 10.0,
+            stroke_width: 1,
+            color: Color::Black,
+            padding: Padding::zero(),
+        }
+    }
+}
+
+/// Lay out and measure the category labels of a chart.
+fn category_labels(surface: &Surface, labels: &[String], font: &str, size: f32) -> Vec<(Text, Bounds)> {
+    labels.iter()
+        .map(|text| axis_label(surface, text.clone(), font, size))
+        .collect()
+}
+
+/// The height the category labels need underneath the axis.
+fn category_label_height(labels: &[(Text, Bounds)]) -> u32 {
+    labels.iter()
+        .map(|l| l.1.height + CHART_LABEL_SPACING)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Draw the category labels centered on the given horizontal positions,
+/// underneath the plot. Labels are moved inwards so they don't stick out of
+/// the chart.
+fn paint_category_labels(surface: &mut Surface, axis_y: u32, labels: &[(Text, Bounds)], centers: &[i64], left: u32, right: u32) {
+    let y = axis_y + 1 + CHART_LABEL_SPACING;
+    for ((label, bounds), &center) in labels.iter().zip(centers) {
+        let label_x = (center - (bounds.width / 2) as i64)
+            .min(right as i64 - bounds.width as i64)
+            .max(left as i64);
+        surface.draw_view(label, label_x as u32, y, *bounds);
+    }
+}
+
+impl View for LineChart {
+    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let available = suggested_bounds - self.padding_data().bounds();
+        Bounds::new(
+            flexible_length(self.width, available.width, suggested_bounds.hint),
+            flexible_length(self.height, available.height, suggested_bounds.hint),
+        ) + self.padding_data().bounds()
+    }
+
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let size = self.bounds(surface, suggested_bounds) - self.padding_data().bounds();
+        let (x, y) = (x + self.padding_data().left, y + self.padding_data().top);
+        let range = self.range.unwrap_or(ValueRange::covering(&self.values, self.threshold, false));
+        let value_labels = value_labels(surface, range, self.decimals, &self.font, self.font_size);
+        let labels = category_labels(surface, &self.labels, &self.font, self.font_size);
+        let inset = CHART_MARKER_SIZE / 2;
+
+        let (plot, axis_y) = paint_axes(
+            surface,
+            (x, y),
+            size,
+            range,
+            Some(&value_labels).filter(|_| self.value_labels),
+            category_label_height(&labels),
+            inset,
+        );
+        if plot.width == 0 || plot.height == 0 {
+            return;
+        }
+
+        let centers: Vec<i64> = (0..labels.len())
+            .map(|i| plot.x_for(i, labels.len()))
+            .collect();
+        paint_category_labels(surface, axis_y, &labels, &centers, plot.x as u32, x + size.width);
+        if let Some(threshold) = self.threshold {
+            paint_threshold(surface, &plot, threshold);
+        }
+        paint_series(surface, &plot, &self.values, self.stroke_width, self.color);
+        if let Some((min, max)) = min_max_indexes(&self.values).filter(|_| self.show_min_max) {
+            let count = self.values.len();
+            paint_marker(surface, (plot.x_for(min, count), plot.y_for(self.values[min])), false, self.color);
+            paint_marker(surface, (plot.x_for(max, count), plot.y_for(self.values[max])), true, self.color);
+        }
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// A bar chart with axes: one bar per value, growing up from zero (or down,
+/// for negative values). The top and bottom of the value range are labeled on
+/// the left, and [labels] go underneath their bars.
+///
+/// Like a [Sparkline], bar charts are flexible in both directions. The bars
+/// share the width equally.
+pub struct BarChart {
+    pub values: Vec<f32>,
+    /// One label per bar, e.g. the hour of the day. Empty by default.
+    pub labels: Vec<String>,
+    /// The width of the chart, or `None` for a flexible width.
+    pub width: Option<u32>,
+    /// The height of the chart, or `None` for a flexible height.
+    pub height: Option<u32>,
+    /// The values at the top and bottom of the value axis. Defaults to `None`,
+    /// which uses the smallest range containing zero, all values, and the
+    /// threshold.
+    pub range: Option<ValueRange>,
+    /// Draw a dashed line at this value in the accent color. Defaults to
+    /// `None`.
+    pub threshold: Option<f32>,
+    /// Mark the top of the smallest (hollow) and largest (filled) bar.
+    /// Defaults to `false`.
+    pub show_min_max: bool,
+    /// Label the top and bottom of the value axis. Defaults to `true`.
+    pub value_labels: bool,
+    /// The number of decimals in the value labels. Defaults to 0.
+    pub decimals: usize,
+    /// The font of the labels. Defaults to [DEFAULT_FONT].
+    pub font: String,
+    /// The size of the labels. Defaults to 10.
+    pub font_size: f32,
+    /// The space between bars in pixels. Defaults to 2.
+    pub bar_spacing: u32,
+    /// The color of the bars and markers. Defaults to [Color::Black].
+    pub color: Color,
+    padding: Padding,
+}
+
+impl BarChart {
+    /// Create a new flexible bar chart for the given values.
+    pub fn new(values: Vec<f32>) -> Self {
+        BarChart {
+            values,
+            labels: Vec::new(),
+            width: None,
+            height: None,
+            range: None,
+            threshold: None,
+            show_min_max: false,
+            value_labels: true,
+            decimals: 0,
+            font: DEFAULT_FONT.to_string(),
+            font_size: 10.0,
+            bar_spacing: 2,
+            color: Color::Black,
+            padding: Padding::zero(),
+        }
+    }
+
+    /// The horizontal start and width of the `index`th bar.
+    fn bar(&self, plot: &Plot, index: usize) -> (i64, u32) {
+        let count = self.values.len() as u32;
+        let spacing = self.bar_spacing * count.saturating_sub(1);
+        let width = plot.width.saturating_sub(spacing) / cmp::max(count, 1);
+        // Center the bars, since the width might not divide evenly.
+        let used = width * count + spacing;
+        let start = plot.x + (plot.width.saturating_sub(used) / 2) as i64;
+        (start + (index as u32 * (width + self.bar_spacing)) as i64, width)
+    }
+}
+
+impl View for BarChart {
+    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let available = suggested_bounds - self.padding_data().bounds();
+        Bounds::new(
+            flexible_length(self.width, available.width, suggested_bounds.hint),
+            flexible_length(self.height, available.height, suggested_bounds.hint),
+        ) + self.padding_data().bounds()
+    }
+
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let size = self.bounds(surface, suggested_bounds) - self.padding_data().bounds();
+        let (x, y) = (x + self.padding_data().left, y + self.padding_data().top);
+        let range = self.range.unwrap_or(ValueRange::covering(&self.values, self.threshold, true));
+        let value_labels = value_labels(surface, range, self.decimals, &self.font, self.font_size);
+        let labels = category_labels(surface, &self.labels, &self.font, self.font_size);
+        let inset = if self.show_min_max { CHART_MARKER_SIZE / 2 } else { 0 };
+
+        let (plot, axis_y) = paint_axes(
+            surface,
+            (x, y),
+            size,
+            range,
+            Some(&value_labels).filter(|_| self.value_labels),
+            category_label_height(&labels),
+            inset,
+        );
+        if plot.width == 0 || plot.height == 0 {
+            return;
+        }
+
+        let style = ShapeStyle::filled(self.color);
+        let baseline = plot.y_for(0.0f32.clamp(range.min, range.max));
+        for (i, value) in self.values.iter().enumerate() {
+            if !value.is_finite() {
+                continue;
+            }
+            let (bar_x, bar_width) = self.bar(&plot, i);
+            let top = plot.y_for(value.clamp(range.min, range.max));
+            let (from, to) = (cmp::min(top, baseline), cmp::max(top, baseline));
+            if bar_x >= 0 && from >= 0 {
+                paint_rounded_rect(surface, bar_x as u32, from as u32, bar_width, (to - from + 1) as u32, 0, style);
+            }
+        }
+
+        let centers: Vec<i64> = (0..labels.len())
+            .map(|i| {
+                let (bar_x, bar_width) = self.bar(&plot, i);
+                bar_x + (bar_width / 2) as i64
+            })
+            .collect();
+        paint_category_labels(surface, axis_y, &labels, &centers, plot.x as u32, x + size.width);
+        if let Some(threshold) = self.threshold {
+            paint_threshold(surface, &plot, threshold);
+        }
+        if let Some((min, max)) = min_max_indexes(&self.values).filter(|_| self.show_min_max) {
+            for (index, filled) in [(min, false), (max, true)] {
+                let (bar_x, bar_width) = self.bar(&plot, index);
+                let center = (bar_x + (bar_width / 2) as i64, plot.y_for(self.values[index]));
+                paint_marker(surface, center, filled, self.color);
+            }
+        }
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// Clamp a progress value to 0.0-1.0. Anything that isn't a number counts as
+/// no progress at all.
+fn clamp_progress(value: f32) -> f32 {
+    if value.is_finite() { value.clamp(0.0, 1.0) } else { 0.0 }
+}
+
+/// The bounds of a view that is flexible along `direction` and `thickness`
+/// pixels across it, just like a [Divider].
+fn bar_bounds(direction: &Direction, thickness: u32, available: Bounds) -> Bounds {
+    let length = |suggested| flexible_length(None, suggested, available.hint);
+    match direction {
+        Direction::Horizontal => Bounds::new(length(available.width), thickness),
+        Direction::Vertical => Bounds::new(thickness, length(available.height)),
+    }
+}
+
+/// A progress bar, e.g. for "72% full".
+///
+/// A horizontal progress bar fills up from left to right and takes up all the
+/// width it's offered, a vertical one fills up from the bottom and takes up
+/// all the height, just like a [Divider]. Across that direction, it's
+/// [thickness] pixels thick.
+pub struct ProgressBar {
+    direction: Direction,
+    /// The progress between 0.0 and 1.0. Values outside that range are
+    /// clamped.
+    pub value: f32,
+    /// The thickness of the bar in pixels. Defaults to 10.
+    pub thickness: u32,
+    /// The radius of the bar's corners. Defaults to 0.
+    pub corner_radius: u32,
+    /// The color of the filled part. Defaults to [Color::Black].
+    pub color: Color,
+    /// The width of the outline around the whole bar. Defaults to 1. The
+    /// outline is always black.
+    pub stroke_width: u32,
+    padding: Padding,
+}
+
+impl ProgressBar {
+    fn new(direction: Direction, value: f32) -> Self {
+        ProgressBar {
+            direction,
+            value,
+            thickness: 10,
+            corner_radius: 0,
+            color: Color::Black,
+            stroke_width: 1,
+            padding: Padding::zero(),
+        }
+    }
+
+    /// Create a new progress bar that fills up from left to right.
+    pub fn horizontal(value: f32) -> Self {
+        ProgressBar::new(Direction::Horizontal, value)
+    }
+
+    /// Create a new progress bar that fills up from the bottom.
+    pub fn vertical(value: f32) -> Self {
+        ProgressBar::new(Direction::Vertical, value)
+    }
+}
+
+impl View for ProgressBar {
+    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        bar_bounds(&self.direction, self.thickness, suggested_bounds - self.padding_data().bounds()) +
+            self.padding_data().bounds()
+    }
+
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let size = self.bounds(surface, suggested_bounds) - self.padding_data().bounds();
+        let (x, y) = (x + self.padding_data().left, y + self.padding_data().top);
+        let outline = ShapeStyle {
+            color: Color::Black,
+            stroke_width: self.stroke_width,
+            fill: None,
+        };
+        paint_rounded_rect(surface, x, y, size.width, size.height, self.corner_radius, outline);
+
+        // The filled part goes inside the outline.
+        let inner_width = size.width.saturating_sub(2 * self.stroke_width);
+        let inner_height = size.height.saturating_sub(2 * self.stroke_width);
+        let progress = clamp_progress(self.value);
+        let (fill_x, fill_y, fill_width, fill_height) = match self.direction {
+            Direction::Horizontal => {
+                let width = (inner_width as f32 * progress).round() as u32;
+                (0, 0, width, inner_height)
+            },
+            Direction::Vertical => {
+                let height = (inner_height as f32 * progress).round() as u32;
+                (0, inner_height - height, inner_width, height)
+            },
+        };
+        paint_rounded_rect(
+            surface,
+            x + self.stroke_width + fill_x,
+            y + self.stroke_width + fill_y,
+            fill_width,
+            fill_height,
+            self.corner_radius.saturating_sub(self.stroke_width),
+            ShapeStyle::filled(self.color),
+        );
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// A semicircular gauge, like a speedometer. The arc fills up clockwise, from
+/// the left end to the right end.
+///
+/// Like a [Circle], a gauge either has a fixed diameter or it's flexible, in
+/// which case it takes up as much space as it can while staying a semicircle:
+/// it's twice as wide as it's high. To show the value in numbers, put a [Text]
+/// on top of it using a [ZStack].
+pub struct Gauge {
+    /// The progress between 0.0 and 1.0. Values outside that range are
+    /// clamped.
+    pub value: f32,
+    /// The diameter of the gauge, or `None` for a flexible diameter.
+    pub diameter: Option<u32>,
+    /// The width of the arc in pixels. Defaults to 10.
+    pub thickness: u32,
+    /// The color of the filled part of the arc. Defaults to [Color::Black].
+    /// The rest of the arc is outlined in black.
+    pub color: Color,
+    padding: Padding,
+}
+
+impl Gauge {
+    /// Create a new flexible gauge.
+    pub fn new(value: f32) -> Self {
+        Gauge {
+            value,
+            diameter: None,
+            thickness: 10,
+            color: Color::Black,
+            padding: Padding::zero(),
+        }
+    }
+
+    fn diameter(&self, available: Bounds) -> u32 {
+        flexible_length(
+            self.diameter,
+            cmp::min(available.width, available.height.saturating_mul(2)),
+            available.hint,
+        )
+    }
+}
+
+impl View for Gauge {
+    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        let diameter = self.diameter(suggested_bounds - self.padding_data().bounds());
+        Bounds::new(diameter, diameter.div_ceil(2)) + self.padding_data().bounds()
+    }
+
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let diameter = self.diameter(suggested_bounds - self.padding_data().bounds());
+        let (x, y) = (x + self.padding_data().left, y + self.padding_data().top);
+        let outer = diameter as f32 / 2.0;
+        let inner = (outer - self.thickness as f32).max(0.0);
+        let progress = clamp_progress(self.value);
+        let fill = surface.rgb(self.color);
+        let outline = surface.rgb(Color::Black);
+
+        // The center is in the middle of the bottom edge.
+        let (center_x, center_y) = (outer, diameter.div_ceil(2) as f32);
+        for py in 0..diameter.div_ceil(2) {
+            for px in 0..diameter {
+                // Test the pixel centers.
+                let dx = px as f32 + 0.5 - center_x;
+                let dy = center_y - (py as f32 + 0.5);
+                let distance = (dx * dx + dy * dy).sqrt();
+                if distance > outer || distance < inner {
+                    continue;
+                }
+
+                // 0.0 at the left end of the arc, 1.0 at the right end.
+                let position = 1.0 - dy.atan2(dx) / std::f32::consts::PI;
+                let color = if position <= progress {
+                    Some(fill)
+                } else if distance > outer - 1.0 || distance < inner + 1.0 || dy < 1.0 {
+                    Some(outline)
+                } else {
+                    None
+                };
+                if let Some(color) = color {
+                    surface.set_pixel(x + px, y + py, color);
+                }
+            }
+        }
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+/// A level indicator made up of separate segments, like the bars of a battery
+/// or signal strength icon. The value is rounded to the closest number of
+/// segments.
+///
+/// Like a [ProgressBar], it's flexible along its direction and [thickness]
+/// pixels across it. Horizontal indicators fill up from the left, vertical ones
+/// from the bottom.
+pub struct LevelIndicator {
+    direction: Direction,
+    /// The level between 0.0 and 1.0. Values outside that range are clamped.
+    pub value: f32,
+    /// The number of segments. Defaults to 5.
+    pub segments: u32,
+    /// The space between segments in pixels. Defaults to 2.
+    pub spacing: u32,
+    /// The thickness of the indicator in pixels. Defaults to 10.
+    pub thickness: u32,
+    /// Draw a small cap at the end, so the indicator looks like a battery.
+    /// Defaults to `false`.
+    pub battery_cap: bool,
+    /// The color of the filled segments. Defaults to [Color::Black]. Empty
+    /// segments are outlined in black.
+    pub color: Color,
+    padding: Padding,
+}
+
+impl LevelIndicator {
+    fn new(direction: Direction, value: f32) -> Self {
+        LevelIndicator {
+            direction,
+            value,
+            segments: 5,
+            spacing: 2,
+            thickness: 10,
+            battery_cap: false,
+            color: Color::Black,
+            padding: Padding::zero(),
+        }
+    }
+
+    /// Create a new level indicator that fills up from left to right.
+    pub fn horizontal(value: f32) -> Self {
+        LevelIndicator::new(Direction::Horizontal, value)
+    }
+
+    /// Create a new level indicator that fills up from the bottom.
+    pub fn vertical(value: f32) -> Self {
+        LevelIndicator::new(Direction::Vertical, value)
+    }
+
+    /// The length of the battery cap along the indicator's direction.
+    fn cap_length(&self) -> u32 {
+        if self.battery_cap { cmp::max(2, self.thickness / 5) } else { 0 }
+    }
+}
+
+impl View for LevelIndicator {
+    fn bounds(&self, _surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+        bar_bounds(&self.direction, self.thickness, suggested_bounds - self.padding_data().bounds()) +
+            self.padding_data().bounds()
+    }
+
+    fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+        let size = self.bounds(surface, suggested_bounds) - self.padding_data().bounds();
+        let (x, y) = (x + self.padding_data().left, y + self.padding_data().top);
+        let length = match self.direction {
+            Direction::Horizontal => size.width,
+            Direction::Vertical => size.height,
+        };
+        let cap = self.cap_length();
+        let segments = cmp::max(self.segments, 1);
+        // There's space between the segments, and between the last segment
+        // and the cap.
+        let gaps = self.spacing * (segments - 1) + if cap > 0 { self.spacing } else { 0 };
+        let segment_length = length
+            .saturating_sub(cap + gaps)
+            .checked_div(segments)
+            .unwrap_or(0);
+        let filled = (clamp_progress(self.value) * segments as f32).round() as u32;
+
+        for i in 0..segments {
+            let style = if i < filled {
+                ShapeStyle::filled(self.color)
+            } else {
+                ShapeStyle::outline()
+            };
+            let offset = i * (segment_length + self.spacing);
+            match self.direction {
+                Direction::Horizontal => {
+                    paint_rounded_rect(surface, x + offset, y, segment_length, size.height, 0, style);
+                },
+                Direction::Vertical => {
+                    let segment_y = (y + size.height).saturating_sub(offset + segment_length);
+                    paint_rounded_rect(surface, x, segment_y, size.width, segment_length, 0, style);
+                },
+            }
+        }
+
+        if cap > 0 {
+            // The cap is centered at the far end, half as thick as the
+            // segments.
+            let used = segments * (segment_length + self.spacing);
+            let (cap_x, cap_y, cap_width, cap_height) = match self.direction {
+                Direction::Horizontal => (x + used, y + size.height / 4, cap, size.height / 2),
+                Direction::Vertical => (x + size.width / 4, (y + size.height).saturating_sub(used + cap), size.width / 2, cap),
+            };
+            paint_rounded_rect(surface, cap_x, cap_y, cap_width, cap_height, 0, ShapeStyle::filled(Color::Black));
+        }
+    }
+
+    fn padding_data(&self) -> Padding {
+        self.padding
+    }
+
+    fn set_padding_data(&mut self, new_padding: Padding) {
+        self.padding = new_padding;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use std::cell::RefCell;
+
+    struct DrawingRegister {
+        // id, x, y
+        drawings: RefCell<Vec<(u32, u32, u32, Bounds)>>,
+    }
+
+    impl DrawingRegister {
+        pub fn new() -> DrawingRegister {
+            DrawingRegister {
+                drawings: RefCell::new(Vec::new()),
+            }
+        }
+
+        pub fn register(&self, view_id: u32, x: u32, y: u32, suggested_bounds: Bounds) {
+            self.drawings.borrow_mut().push((view_id, x, y, suggested_bounds));
+        }
+        
+        pub fn was_drawn_at(&self, view_id: u32, x: u32, y: u32) -> bool {
+            for d in self.drawings.borrow().iter() {
+                if d.0 == view_id && d.1 == x && d.2 == y {
+                    return true;
+                }
+            }
+
+            false
+        }
+
+        pub fn was_drawn_with_bounds(&self, view_id: u32, bounds: Bounds) -> bool {
+            for d in self.drawings.borrow().iter() {
+                if d.0 == view_id && d.3.width == bounds.width && d.3.height == bounds.height {
+                    return true;
+                }
+            }
+
+            false
+        }
+    }
+
+    struct MonitorWrapper {
+        pub id: u32,
+        pub drawing_register: Rc<DrawingRegister>,
+        pub child: Box<dyn View>,
+    }
+
+    impl MonitorWrapper {
+        pub fn new(id: u32, drawing_register: Rc<DrawingRegister>, child: Box<dyn View>) -> Self {
+            MonitorWrapper { id, drawing_register, child }
+        }
+    }
+
+    impl View for MonitorWrapper {
+        fn bounds(&self, surface: &Surface, suggested_bounds: Bounds) -> Bounds {
+            self.child.bounds(surface, suggested_bounds)
+        }
+
+        fn draw(&self, surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+            self.drawing_register.as_ref().register(self.id, x, y, suggested_bounds);
+            self.child.draw(surface, x, y, suggested_bounds);
+        }
+
+        fn padding_data(&self) -> Padding {
+            Padding::zero()
+        }
+
+        fn set_padding_data(&mut self, _: Padding) {
+        }
+    }
+
+    struct TestView {
+        pub id: u32,
+        pub width: u32,
+        pub height: u32,
+        pub drawing_register: Option<Rc<DrawingRegister>>,
+        pub padding: Padding,
+    }
+
+    impl TestView {
+        pub fn new(width: u32, height: u32) -> TestView {
+            TestView {
+                id: 0,
+                width,
+                height,
+                drawing_register: None,
+                padding: Padding::zero(),
+            }
+        }
+
+        pub fn monitored(id: u32, register: Rc<DrawingRegister>, width: u32, height: u32) -> TestView {
+            TestView {
+                id,
+                width,
+                height,
+                drawing_register: Some(register),
+                padding: Padding::zero(),
+            }
+        }
+    }
+
+    impl View for TestView {
+        fn bounds(&self, _surface: &Surface, _suggested_bounds: Bounds) -> Bounds {
+            Bounds::new(self.width, self.height)
+        }
+
+        fn draw(&self, _surface: &mut Surface, x: u32, y: u32, suggested_bounds: Bounds) {
+            if self.drawing_register.is_none() {
+                return;
+            }
+
+            self.drawing_register.as_ref().unwrap().register(self.id, x, y, suggested_bounds);
+        }
+
+        fn padding_data(&self) -> Padding {
+            self.padding
+        }
+
+        fn set_padding_data(&mut self, new_padding: Padding) {
+            self.padding = new_padding;
+        }
+    }
+
+    #[test]
+    fn test_empty_vstack_has_zero_width() {
+        let surface = Surface::new(300, 300).unwrap();
+        let vstack = VStack::new();
+        assert_eq!(0, vstack.bounds(&surface, surface.bounds()).width);
+    }
+
+    #[test]
+    fn test_vstack_has_maximum_width_of_all_children() {
+        let surface = Surface::new(300, 300).unwrap();
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::new(50, 10)));
+        vstack.views.push(Box::new(TestView::new(150, 10)));
+        vstack.views.push(Box::new(TestView::new(100, 10)));
+        assert_eq!(150, vstack.bounds(&surface, surface.bounds()).width);
+    }
+
+    #[test]
+    fn test_empty_vstack_has_zero_height() {
+        let surface = Surface::new(300, 300).unwrap();
+        let vstack = VStack::new();
+        assert_eq!(0, vstack.bounds(&surface, surface.bounds()).height);
+    }
+
+    #[test]
+    fn test_vstack_has_total_height_of_all_children() {
+        let surface = Surface::new(300, 300).unwrap();
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::new(50, 50)));
+        vstack.views.push(Box::new(TestView::new(50, 100)));
+        vstack.views.push(Box::new(TestView::new(50, 10)));
+        assert_eq!(160, vstack.bounds(&surface, surface.bounds()).height);
+    }
+
+    #[test]
+    fn test_vstack_height_includes_spacing() {
+        let surface = Surface::new(300, 300).unwrap();
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::new(50, 50)));
+        vstack.views.push(Box::new(TestView::new(50, 100)));
+        vstack.views.push(Box::new(TestView::new(50, 10)));
+        vstack.spacing = 5;
+        assert_eq!(170, vstack.bounds(&surface, surface.bounds()).height);
+    }
+    
+    #[test]
+    fn test_vstack_draws_left_aligned_elements_at_original_x() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 50, 100)));
+        let bounds = surface.bounds();
+        vstack.draw(&mut surface, 100, 100, bounds);
+        assert!(register.was_drawn_at(1, 100, 100));
+        assert!(register.was_drawn_at(2, 100, 150));
+    }
+
+    #[test]
+    fn test_vstack_draws_right_aligned_elements() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 100)));
+        vstack.align = HAlign::Right;
+        let bounds = surface.bounds();
+        vstack.draw(&mut surface, 100, 100, bounds - Bounds::new(100, 100));
+        assert!(register.was_drawn_at(1, 450, 100));
+        assert!(register.was_drawn_at(2, 425, 150));
+    }
+
+    #[test]
+    fn test_vstack_right_aligns_elements_inside_its_padding() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        vstack.align = HAlign::Right;
+        vstack.padding(Edge::Left, 20);
+        vstack.padding(Edge::Right, 10);
+        vstack.draw(&mut surface, 100, 100, Bounds::new(400, 400));
+        assert!(register.was_drawn_at(1, 440, 100));
+    }
+
+    #[test]
+    fn test_hstack_bottom_aligns_elements_inside_its_padding() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        hstack.align = VAlign::Bottom;
+        hstack.padding(Edge::Top, 20);
+        hstack.padding(Edge::Bottom, 10);
+        hstack.draw(&mut surface, 100, 100, Bounds::new(400, 400));
+        assert!(register.was_drawn_at(1, 100, 440));
+    }
+
+    #[test]
+    fn test_vstack_centers_elements_inside_its_padding() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        vstack.align = HAlign::Center;
+        vstack.padding(Edge::Left, 20);
+        vstack.padding(Edge::Right, 10);
+        vstack.draw(&mut surface, 100, 100, Bounds::new(400, 400));
+        assert!(register.was_drawn_at(1, 280, 100));
+    }
+
+    #[test]
+    fn test_hstack_centers_elements_inside_its_padding() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        hstack.align = VAlign::Center;
+        hstack.padding(Edge::Top, 20);
+        hstack.padding(Edge::Bottom, 10);
+        hstack.draw(&mut surface, 100, 100, Bounds::new(400, 400));
+        assert!(register.was_drawn_at(1, 100, 280));
+    }
+
+    #[test]
+    fn test_vstack_draws_center_aligned_elements() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 100)));
+        vstack.align = HAlign::Center;
+        let bounds = surface.bounds();
+        vstack.draw(&mut surface, 100, 100, bounds - Bounds::new(100, 100));
+        assert!(register.was_drawn_at(1, 275, 100));
+        assert!(register.was_drawn_at(2, 262, 150));
+    }
+
+    #[test]
+    fn test_vstack_leaves_spacing_between_elements() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 100)));
+        vstack.spacing = 10;
+        let bounds = surface.bounds();
+        vstack.draw(&mut surface, 100, 100, bounds - Bounds::new(100, 100));
+        assert!(register.was_drawn_at(1, 100, 100));
+        assert!(register.was_drawn_at(2, 100, 160));
+    }
+
+    #[test]
+    // TODO: Find a better name for this.
+    fn test_vstack_spacer() {
+        let surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 100)));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        assert_eq!(150, vstack.bounds(&surface, bounds.zero_hinted()).height);
+        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.optimally_hinted()).height);
+        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.infinitely_hinted()).height);
+    }
+
+    #[test]
+    fn test_vstack_layouts_zero_views() {
+        let surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 0)));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 100)));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        assert_eq!(100, vstack.bounds(&surface, bounds.zero_hinted()).height);
+        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.optimally_hinted()).height);
+        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.infinitely_hinted()).height);
+    }
+
+    #[test]
+    fn test_vstack_layouts_views_that_are_too_big() {
+        let surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 100)));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 50, 100)));
+        let bounds = Bounds::new(50, 50);
+        assert_eq!(200, vstack.bounds(&surface, bounds.zero_hinted()).height);
+        assert_eq!(200, vstack.bounds(&surface, bounds.optimally_hinted()).height);
+        assert_eq!(200, vstack.bounds(&surface, bounds.infinitely_hinted()).height);
+    }
+
+    #[test]
+    fn test_vstack_layouts_multiple_spacers_zero_hinted() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 100)));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 50, 75)));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(TestView::monitored(3, register.clone(), 50, 50)));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        // Zero-hinted, so both spacers should just collapse.
+        assert_eq!(225, vstack.bounds(&surface, bounds.zero_hinted()).height);
+        vstack.draw(&mut surface, 0, 0, bounds.zero_hinted());
+        assert!(register.was_drawn_at(1, 0, 0));
+        assert!(register.was_drawn_at(2, 0, 100));
+        assert!(register.was_drawn_at(3, 0, 175));
+    }
+
+    #[test]
+    fn test_vstack_layouts_multiple_spacers_optimally_hinted() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 100)));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 50, 75)));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(TestView::monitored(3, register.clone(), 50, 50)));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        // Optimally-hinted, so let spacers expand up to max.
+        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.optimally_hinted()).height);
+        vstack.draw(&mut surface, 0, 0, bounds.optimally_hinted());
+        // The first one is just at the top. After that, we'd expect the first
+        // and second spacers to take up equal height, so 87 and 88 pixels.
+        // (400 - (100 + 75 + 50)) / 2 = (400 - 225) / 2 = 175 / 2 = 87.5
+        assert!(register.was_drawn_at(1, 0, 0));
+        assert!(register.was_drawn_at(2, 0, 187));
+        assert!(register.was_drawn_at(3, 0, 350));
+    }
+
+    #[test]
+    fn test_vstack_layouts_multiple_spacers_infinitely_hinted() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 100)));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 50, 75)));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(TestView::monitored(3, register.clone(), 50, 50)));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        // Infinitely-hinted, so let spacers expand up to max.
+        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.infinitely_hinted()).height);
+        vstack.draw(&mut surface, 0, 0, bounds.infinitely_hinted());
+        // The first one is just at the top. After that, we'd expect the first
+        // and second spacers to take up equal height, so 87 and 88 pixels.
+        // (400 - (100 + 75 + 50)) / 2 = (400 - 225) / 2 = 175 / 2 = 87.5
+        assert!(register.was_drawn_at(1, 0, 0));
+        assert!(register.was_drawn_at(2, 0, 187));
+        assert!(register.was_drawn_at(3, 0, 350));
+    }
+
+    #[test]
+    fn test_vstack_layouts_purely_spacers_zero_hinted() {
+        let surface = Surface::new(500, 500).unwrap();
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        // Zero-hinted, so the spacers should collapse to zero, which means the
+        // entire VStack collapses to zero.
+        assert_eq!(0, vstack.bounds(&surface, bounds.zero_hinted()).height);
+    }
+
+    #[test]
+    fn test_vstack_layouts_purely_spacers_optimally_hinted() {
+        let surface = Surface::new(500, 500).unwrap();
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        // Optimally-hinted, so spacers expand to the maximum possible.
+        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.optimally_hinted()).height);
+    }
+
+    #[test]
+    fn test_vstack_layouts_purely_spacers_infinitely_hinted() {
+        let surface = Surface::new(500, 500).unwrap();
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        // Infinitely-hinted, so spacers expand to the maximum possible.
+        assert_eq!(bounds.height, vstack.bounds(&surface, bounds.infinitely_hinted()).height);
+    }
+
+    #[test]
+    fn test_vstack_layouts_nested_vstack() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut inner_vstack = VStack::new();
+        inner_vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        inner_vstack.views.push(Box::new(Spacer::vertical()));
+        inner_vstack.views.push(Box::new(TestView::monitored(2, register.clone(), 80, 80)));
+        let mut outer_vstack = VStack::new();
+        outer_vstack.views.push(Box::new(inner_vstack));
+        outer_vstack.views.push(Box::new(TestView::monitored(3, register.clone(), 100, 100)));
+        outer_vstack.views.push(Box::new(MonitorWrapper::new(4, register.clone(), Box::new(Spacer::vertical()))));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        outer_vstack.draw(&mut surface, 0, 0, bounds.infinitely_hinted());
+        // First, the least-flexible view — the test view 3 of height 100 — will
+        // be offered 133 pixels, but it'll only need 100 pixels. Then, 150 of
+        // the remaining 300 pixels will be offered to the inner vstack, since
+        // that's _slightly_ less flexible than the bottom spacer. It'll gladly
+        // take the entire 150 pixels, expanding its spacer to 20 pixels. After
+        // that, the bottom spacer will get the remaining 150 pixels.
+        assert!(register.was_drawn_at(1, 0, 0));
+        assert!(register.was_drawn_at(2, 0, 70));
+        assert!(register.was_drawn_at(3, 0, 150));
+        assert!(register.was_drawn_at(4, 0, 250));
+    }
+
+    #[test]
+    fn test_empty_hstack_has_zero_height() {
+        let surface = Surface::new(300, 300).unwrap();
+        let hstack = HStack::new();
+        assert_eq!(0, hstack.bounds(&surface, surface.bounds()).height);
+    }
+
+    #[test]
+    fn test_empty_hstack_has_maximum_height_of_all_children() {
+        let surface = Surface::new(300, 300).unwrap();
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::new(10, 50)));
+        hstack.views.push(Box::new(TestView::new(10, 150)));
+        hstack.views.push(Box::new(TestView::new(10, 100)));
+        assert_eq!(150, hstack.bounds(&surface, surface.bounds()).height);
+    }
+
+    #[test]
+    fn test_empty_hstack_has_zero_width() {
+        let surface = Surface::new(300, 300).unwrap();
+        let hstack = HStack::new();
+        assert_eq!(0, hstack.bounds(&surface, surface.bounds()).width);
+    }
+
+    #[test]
+    fn test_hstack_with_inflexible_children_expands_to_their_total_width() {
+        let surface = Surface::new(300, 300).unwrap();
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::new(50, 50)));
+        hstack.views.push(Box::new(TestView::new(100, 50)));
+        hstack.views.push(Box::new(TestView::new(10, 50)));
+        assert_eq!(160, hstack.bounds(&surface, surface.bounds()).width);
+    }
+
+    #[test]
+    fn test_hstack_width_includes_spacing() {
+        let surface = Surface::new(300, 300).unwrap();
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::new(50, 50)));
+        hstack.views.push(Box::new(TestView::new(100, 50)));
+        hstack.views.push(Box::new(TestView::new(10, 50)));
+        hstack.spacing = 5;
+        assert_eq!(170, hstack.bounds(&surface, surface.bounds()).width);
+    }
+
+    #[test]
+    fn test_hstack_draws_top_aligned_elements_at_original_y() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 100, 50)));
+        let bounds = surface.bounds();
+        hstack.draw(&mut surface, 100, 100, bounds);
+        assert!(register.was_drawn_at(1, 100, 100));
+        assert!(register.was_drawn_at(2, 150, 100));
+    }
+
+    #[test]
+    fn test_hstack_draws_bottom_aligned_elements() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 100, 75)));
+        hstack.align = VAlign::Bottom;
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        hstack.draw(&mut surface, 100, 100, bounds);
+        assert!(register.was_drawn_at(1, 100, 450));
+        assert!(register.was_drawn_at(2, 150, 425));
+    }
+
+    #[test]
+    fn test_hstack_draws_center_aligned_elements() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 100, 75)));
+        hstack.align = VAlign::Center;
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        hstack.draw(&mut surface, 100, 100, bounds);
+        assert!(register.was_drawn_at(1, 100, 275));
+        assert!(register.was_drawn_at(1, 150, 262));
+    }
+
+    #[test]
+    fn test_hstack_leaves_spacing_between_elements() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 100, 75)));
+        hstack.spacing = 10;
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        hstack.draw(&mut surface, 100, 100, bounds);
+        assert!(register.was_drawn_at(1, 100, 100));
+        assert!(register.was_drawn_at(2, 160, 100));
+    }
+
+    #[test]
+    fn test_hstack_expands_spacers() {
+        let surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 100, 75)));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        assert_eq!(150, hstack.bounds(&surface, bounds.zero_hinted()).width);
+        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.optimally_hinted()).width);
+        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.infinitely_hinted()).width);
+    }
+
+    #[test]
+    fn test_hstack_layouts_zero_views() {
+        let surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 0, 50)));
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 100, 75)));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        assert_eq!(100, hstack.bounds(&surface, bounds.zero_hinted()).width);
+        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.optimally_hinted()).width);
+        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.infinitely_hinted()).width);
+    }
+
+    #[test]
+    fn test_hstack_layouts_views_that_are_too_big() {
+        let surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 100, 50)));
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 100, 50)));
+        let bounds = Bounds::new(50, 50);
+        assert_eq!(200, hstack.bounds(&surface, bounds.zero_hinted()).width);
+        assert_eq!(200, hstack.bounds(&surface, bounds.optimally_hinted()).width);
+        assert_eq!(200, hstack.bounds(&surface, bounds.infinitely_hinted()).width);
+    }
+
+    #[test]
+    fn test_hstack_layouts_multiple_spacers_zero_hinted() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 100, 50)));
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 50)));
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        hstack.views.push(Box::new(TestView::monitored(3, register.clone(), 50, 50)));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        // Zero-hinted, so both spacers should just collapse.
+        assert_eq!(225, hstack.bounds(&surface, bounds.zero_hinted()).width);
+        hstack.draw(&mut surface, 0, 0, bounds.zero_hinted());
+        assert!(register.was_drawn_at(1, 0, 0));
+        assert!(register.was_drawn_at(2, 100, 0));
+        assert!(register.was_drawn_at(3, 175, 0));
+    }
+
+    #[test]
+    fn test_hstack_layouts_multiple_spacers_optimally_hinted() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 100, 50)));
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 50)));
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        hstack.views.push(Box::new(TestView::monitored(3, register.clone(), 50, 50)));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        // Optimally-hinted, so let spacers expand up to max.
+        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.optimally_hinted()).width);
+        hstack.draw(&mut surface, 0, 0, bounds.optimally_hinted());
+        // The first one is just at the top. After that, we'd expect the first
+        // and second spacers to take up equal height, so 87 and 88 pixels.
+        // (400 - (100 + 75 + 50)) / 2 = (400 - 225) / 2 = 175 / 2 = 87.5
+        assert!(register.was_drawn_at(1, 0, 0));
+        assert!(register.was_drawn_at(2, 187, 0));
+        assert!(register.was_drawn_at(3, 350, 0));
+    }
+
+    #[test]
+    fn test_hstack_layouts_multiple_spacers_infinitely_hinted() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 100, 50)));
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 75, 50)));
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        hstack.views.push(Box::new(TestView::monitored(3, register.clone(), 50, 50)));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        // Infinitely-hinted, so let spacers expand up to max.
+        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.optimally_hinted()).width);
+        hstack.draw(&mut surface, 0, 0, bounds.optimally_hinted());
+        // The first one is just at the top. After that, we'd expect the first
+        // and second spacers to take up equal height, so 87 and 88 pixels.
+        // (400 - (100 + 75 + 50)) / 2 = (400 - 225) / 2 = 175 / 2 = 87.5
+        assert!(register.was_drawn_at(1, 0, 0));
+        assert!(register.was_drawn_at(2, 187, 0));
+        assert!(register.was_drawn_at(3, 350, 0));
+    }
+
+    #[test]
+    fn test_hstack_layouts_purely_spacers_zero_hinted() {
+        let surface = Surface::new(500, 500).unwrap();
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        // Zero-hinted, so the spacers should collapse to zero, which means the
+        // entire hstack collapses to zero.
+        assert_eq!(0, hstack.bounds(&surface, bounds.zero_hinted()).width);
+    }
+
+    #[test]
+    fn test_hstack_layouts_purely_spacers_optimally_hinted() {
+        let surface = Surface::new(500, 500).unwrap();
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        // Optimally-hinted, so spacers expand to the maximum possible.
+        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.optimally_hinted()).width);
+    }
+
+    #[test]
+    fn test_hstack_layouts_purely_spacers_infinitely_hinted() {
+        let surface = Surface::new(500, 500).unwrap();
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        // Infinitely-hinted, so spacers expand to the maximum possible.
+        assert_eq!(bounds.width, hstack.bounds(&surface, bounds.infinitely_hinted()).width);
+    }
+
+    #[test]
+    fn test_hstack_layouts_nested_hstack() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
         let mut inner_hstack = HStack::new();
         inner_hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 50)));
         inner_hstack.views.push(Box::new(Spacer::horizontal()));
@@ -1724,229 +5659,1982 @@ mod tests {
         outer_hstack.views.push(Box::new(TestView::monitored(3, register.clone(), 100, 100)));
         outer_hstack.views.push(Box::new(MonitorWrapper::new(4, register.clone(), Box::new(Spacer::horizontal()))));
         let bounds = surface.bounds() - Bounds::new(100, 100);
-        outer_hstack.draw(&mut surface, 0, 0, bounds.infinitely_hinted());
-        // First, the least-flexible view — the test view 3 of height 100 — will
-        // be offered 133 pixels, but it'll only need 100 pixels. Then, 150 of
-        // the remaining 300 pixels will be offered to the inner hstack, since
-        // that's _slightly_ less flexible than the bottom spacer. It'll gladly
-        // take the entire 150 pixels, expanding its spacer to 20 pixels. After
-        // that, the bottom spacer will get the remaining 150 pixels.
+        outer_hstack.draw(&mut surface, 0, 0, bounds.infinitely_hinted());
+        // First, the least-flexible view — the test view 3 of height 100 — will
+        // be offered 133 pixels, but it'll only need 100 pixels. Then, 150 of
+        // the remaining 300 pixels will be offered to the inner hstack, since
+        // that's _slightly_ less flexible than the bottom spacer. It'll gladly
+        // take the entire 150 pixels, expanding its spacer to 20 pixels. After
+        // that, the bottom spacer will get the remaining 150 pixels.
+        assert!(register.was_drawn_at(1, 0, 0));
+        assert!(register.was_drawn_at(2, 70, 0));
+        assert!(register.was_drawn_at(3, 150, 0));
+        assert!(register.was_drawn_at(4, 250, 0));
+    }
+
+    #[test]
+    fn test_can_mix_hstack_and_vstack() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut top_hstack = HStack::new();
+        top_hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 30)));
+        top_hstack.views.push(Box::new(Spacer::horizontal()));
+        top_hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 70, 35)));
+        let mut bottom_hstack = HStack::new();
+        bottom_hstack.views.push(Box::new(TestView::monitored(3, register.clone(), 50, 40)));
+        bottom_hstack.views.push(Box::new(TestView::monitored(4, register.clone(), 70, 40)));
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(top_hstack));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(TestView::monitored(5, register.clone(), 150, 100)));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(bottom_hstack));
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        vstack.draw(&mut surface, 0, 0, bounds.infinitely_hinted());
+        // The first view should be in the top left corner.
+        assert!(register.was_drawn_at(1, 0, 0));
+        // The second view in the top right corner.
+        assert!(register.was_drawn_at(2, 400 - 70, 0));
+        // The third view is in the bottom left corner. The fourth one follows
+        // immediately.
+        assert!(register.was_drawn_at(3, 0, 400 - 40));
+        assert!(register.was_drawn_at(4, 50, 400 - 40));
+        // Number five is somewhere in the middle. We have the top hstack taking
+        // up 35 pixels. And the bottom hstack taking up 40 pixels. So that
+        // leaves 400 - (35 + 40) = 325 pixels. View number five itself takes up
+        // 100 pixels, so we have 225 pixels to distribute among the two
+        // vertical spacers. We size them equally, so 112 pixels for the first
+        // and 113 for the second.
+        assert!(register.was_drawn_at(5, 0, 35 + 112));
+    }
+
+    #[test]
+    fn test_can_set_padding_on_view() {
+        let mut view = TestView::new(300, 300);
+        assert_eq!(0, view.padding.left);
+        assert_eq!(0, view.padding.right);
+        assert_eq!(0, view.padding.top);
+        assert_eq!(0, view.padding.bottom);
+        view.padding(Edge::Left, 1);
+        view.padding(Edge::Right, 2);
+        view.padding(Edge::Top, 3);
+        view.padding(Edge::Bottom, 4);
+        assert_eq!(1, view.padding.left);
+        assert_eq!(2, view.padding.right);
+        assert_eq!(3, view.padding.top);
+        assert_eq!(4, view.padding.bottom);
+    }
+
+    #[test]
+    fn test_vstack_renders_top_and_left_padding() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 150, 100)));
+        vstack.padding(Edge::Left, 10);
+        vstack.padding(Edge::Right, 5);
+        vstack.padding(Edge::Top, 15);
+        vstack.padding(Edge::Bottom, 2);
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        assert_eq!(150 + 15, vstack.bounds(&surface, bounds.zero_hinted()).width);
+        assert_eq!(150 + 15, vstack.bounds(&surface, bounds.optimally_hinted()).width);
+        assert_eq!(150 + 15, vstack.bounds(&surface, bounds.infinitely_hinted()).width);
+        assert_eq!(100 + 17, vstack.bounds(&surface, bounds.zero_hinted()).height);
+        assert_eq!(100 + 17, vstack.bounds(&surface, bounds.optimally_hinted()).height);
+        assert_eq!(100 + 17, vstack.bounds(&surface, bounds.infinitely_hinted()).height);
+        vstack.draw(&mut surface, 0, 0, bounds.infinitely_hinted());
+        assert!(register.was_drawn_at(1, 10, 15));
+    }
+
+    #[test]
+    fn test_vstack_padding_cuts_into_space_for_views() {
+        // When the VStack includes a spacer like this:
+        //
+        // +-------------------+
+        // |    Test View      |
+        // |   (100 x 150)     |
+        // +-------------------+
+        // |      Spacer       |
+        // +-------------------+
+        // |    Test View      |
+        // |    (75 x 75)      |
+        // +-------------------+
+        //
+        // Since this is a VStack, let's think about the height of the thing
+        // first. Let's say that we have 200 px of height for the entire thing.
+        // Then in all three hinting modes, the VStack will take up 225 px.
+        // Since the test views are not willing to flex and they need a minimum
+        // of 225 px, no matter the suggested bounds for the view.
+        //
+        // Padding is also not flexible. So, in this situation, the height of
+        // the VStack would increase further by the top and bottom padding, no
+        // matter the suggested bounds.
+        //
+        // If we expand the suggested bounds to _just_ cover the required height
+        // of the views plus the padding -- let's say that we pad by 10 px at
+        // the top and by 5 px at the bottom, so 240 px in total -- then the
+        // spacer would always collapse to zero. Everything beyond 240 px would
+        // be left over for the spacer in infinitely- and optimally-hinted modes.
+        //
+        // As for the horizontal extend of the VStack: that part is reasonably
+        // easy: the VStack just adds its own padding to the total width
+        // required.
+        //
+        // To test this, we'll embed a VStack in another VStack, like this:
+        //
+        // +--------------------------+
+        // |        Test View         |
+        // +--------------------------+
+        // |    VStack w/ padding     |
+        // | +----------------------+ |
+        // | |      Test View       | |
+        // | +----------------------+ |
+        // | |        Spacer        | |
+        // | +----------------------+ |
+        // | |      Test View       | |
+        // | +----------------------+ |
+        // +--------------------------+
+        // |        Test View         |
+        // +--------------------------+
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut inner_stack = VStack::new();
+        inner_stack.views.push(Box::new(TestView::monitored(2, register.clone(), 100, 150)));
+        inner_stack.views.push(Box::new(Spacer::vertical()));
+        inner_stack.views.push(Box::new(TestView::monitored(3, register.clone(), 75, 75)));
+        inner_stack.padding(Edge::Top, 10);
+        inner_stack.padding(Edge::Bottom, 5);
+        inner_stack.padding(Edge::Left, 15);
+        inner_stack.padding(Edge::Right, 2);
+        let mut outer_stack = VStack::new();
+        outer_stack.views.push(Box::new(TestView::monitored(1, register.clone(), 100, 40)));
+        outer_stack.views.push(Box::new(inner_stack));
+        outer_stack.views.push(Box::new(TestView::monitored(4, register.clone(), 80, 50)));
+        // Give the entire outer stack 350 px of height. That should leave
+        // 350 - 150 - 75 - 40 - 50 - 10 - 5 = 20 px for the spacer.
+        let bounds = surface.bounds() - Bounds::new(150, 150);
+        outer_stack.draw(&mut surface, 0, 0, bounds.optimally_hinted());
+        assert!(register.was_drawn_at(1, 0, 0));
+        // 15 (left padding) x 40 + 10 (top padding)
+        assert!(register.was_drawn_at(2, 15, 50));
+        // Spacer in between 2 and 3.
+        // 15 (left padding) x 40 + 20 (spacer) + 150 + 10 (top padding)
+        assert!(register.was_drawn_at(3, 15, 220));
+        // 0 x 40 + 20 (spacer) + 150 + 75 + 10 (top padding) + 5 (bottom padding)
+        assert!(register.was_drawn_at(4, 0, 300));
+    }
+
+    #[test]
+    fn test_hstack_renders_top_and_left_padding() {
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 150, 100)));
+        hstack.padding(Edge::Left, 10);
+        hstack.padding(Edge::Right, 5);
+        hstack.padding(Edge::Top, 15);
+        hstack.padding(Edge::Bottom, 2);
+        let bounds = surface.bounds() - Bounds::new(100, 100);
+        assert_eq!(150 + 15, hstack.bounds(&surface, bounds.zero_hinted()).width);
+        assert_eq!(150 + 15, hstack.bounds(&surface, bounds.optimally_hinted()).width);
+        assert_eq!(150 + 15, hstack.bounds(&surface, bounds.infinitely_hinted()).width);
+        assert_eq!(100 + 17, hstack.bounds(&surface, bounds.zero_hinted()).height);
+        assert_eq!(100 + 17, hstack.bounds(&surface, bounds.optimally_hinted()).height);
+        assert_eq!(100 + 17, hstack.bounds(&surface, bounds.infinitely_hinted()).height);
+        hstack.draw(&mut surface, 0, 0, bounds.infinitely_hinted());
+        assert!(register.was_drawn_at(1, 10, 15));
+    }
+
+    #[test]
+    fn test_hstack_padding_cuts_into_space_for_views() {
+        // The argument for the padding goes pretty much exactly like in the
+        // VStack test. We use the following test setup:
+        //
+        // +--------+---------------------------------+--------+
+        // |        |       HStack w/ padding         |        |
+        // |  Test  | +--------+----------+--------+  |  Test  |
+        // |  View  | |  Test  |  Spacer  |  Test  |  |  View  |
+        // |        | |  View  |          |  View  |  |        |
+        // |        | +--------+----------+--------+  |        |
+        // +--------+---------------------------------+--------+
+        let mut surface = Surface::new(500, 500).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut inner_stack = HStack::new();
+        inner_stack.views.push(Box::new(TestView::monitored(2, register.clone(), 150, 100)));
+        inner_stack.views.push(Box::new(Spacer::horizontal()));
+        inner_stack.views.push(Box::new(TestView::monitored(3, register.clone(), 75, 75)));
+        inner_stack.padding(Edge::Left, 10);
+        inner_stack.padding(Edge::Right, 5);
+        inner_stack.padding(Edge::Top, 15);
+        inner_stack.padding(Edge::Bottom, 2);
+        let mut outer_stack = HStack::new();
+        outer_stack.views.push(Box::new(TestView::monitored(1, register.clone(), 40, 100)));
+        outer_stack.views.push(Box::new(inner_stack));
+        outer_stack.views.push(Box::new(TestView::monitored(4, register.clone(), 50, 80)));
+        // Give the entire outer stack 350 px of width. That should leave
+        // 350 - 150 - 75 - 40 - 50 - 10 - 5 = 20 px for the spacer.
+        let bounds = surface.bounds() - Bounds::new(150, 150);
+        outer_stack.draw(&mut surface, 0, 0, bounds.optimally_hinted());
         assert!(register.was_drawn_at(1, 0, 0));
-        assert!(register.was_drawn_at(2, 70, 0));
-        assert!(register.was_drawn_at(3, 150, 0));
-        assert!(register.was_drawn_at(4, 250, 0));
+        // 40 + 10 (left padding) x 15 (top padding)
+        assert!(register.was_drawn_at(2, 50, 15));
+        // Spacer in between 2 and 3.
+        // 40 + 20 (spacer) + 150 + 10 (left padding) x 15 (top padding)
+        assert!(register.was_drawn_at(3, 220, 15));
+        // 40 + 20 (spacer) + 150 + 75 + 10 (left padding) + 5 (right padding) x 0
+        assert!(register.was_drawn_at(4, 300, 0));
+    }
+
+    fn distinct_gray_levels(surface: &Surface) -> Vec<u8> {
+        let mut levels: Vec<u8> = surface.img.pixels().map(|p| p.0[0]).collect();
+        levels.sort();
+        levels.dedup();
+        levels
+    }
+
+    #[test]
+    fn test_threshold_text_is_pure_black_and_white() {
+        let mut surface = Surface::new(100, 40).unwrap();
+        let text = Text::new(String::from("Hamburg"), 13.0, "Roboto");
+        let bounds = surface.bounds();
+        text.draw(&mut surface, 0, 0, bounds);
+        assert_eq!(vec![0, 255], distinct_gray_levels(&surface));
+    }
+
+    #[test]
+    fn test_grayscale_text_blends_coverage_into_background() {
+        let mut surface = Surface::new(100, 40).unwrap();
+        let mut text = Text::new(String::from("Hamburg"), 13.0, "Roboto");
+        text.rendering = Some(TextRendering::Grayscale);
+        let bounds = surface.bounds();
+        text.draw(&mut surface, 0, 0, bounds);
+        let levels = distinct_gray_levels(&surface);
+        assert!(levels.len() > 2);
+        assert!(levels.iter().any(|&l| l > 0 && l < 255));
+    }
+
+    #[test]
+    fn test_text_uses_surface_default_rendering() {
+        let mut surface = Surface::new(100, 40).unwrap();
+        surface.text_rendering = TextRendering::Grayscale;
+        let text = Text::new(String::from("Hamburg"), 13.0, "Roboto");
+        let bounds = surface.bounds();
+        text.draw(&mut surface, 0, 0, bounds);
+        assert!(distinct_gray_levels(&surface).len() > 2);
+    }
+
+    #[test]
+    fn test_blend_pixel_mixes_with_existing_color() {
+        let mut surface = Surface::new(1, 1).unwrap();
+        surface.blend_pixel(0, 0, image::Rgb([0, 0, 0]), 0);
+        assert_eq!(image::Rgb([255, 255, 255]), *surface.img.get_pixel(0, 0));
+        surface.blend_pixel(0, 0, image::Rgb([0, 0, 0]), 128);
+        assert_eq!(image::Rgb([127, 127, 127]), *surface.img.get_pixel(0, 0));
+        surface.blend_pixel(0, 0, image::Rgb([0, 0, 0]), 255);
+        assert_eq!(image::Rgb([0, 0, 0]), *surface.img.get_pixel(0, 0));
+    }
+
+    #[test]
+    fn test_accent_color_resolves_against_palette() {
+        let mut surface = Surface::new(1, 1).unwrap();
+        assert_eq!(image::Rgb([0, 0, 0]), surface.rgb(Color::Accent));
+        surface.palette = Palette::BlackWhiteRed;
+        assert_eq!(image::Rgb([255, 0, 0]), surface.rgb(Color::Accent));
+        surface.palette = Palette::BlackWhiteYellow;
+        assert_eq!(image::Rgb([255, 255, 0]), surface.rgb(Color::Accent));
+    }
+
+    #[test]
+    fn test_quantize_snaps_pixels_to_palette() {
+        let mut surface = Surface::new(3, 1).unwrap();
+        surface.palette = Palette::BlackWhiteRed;
+        surface.img.put_pixel(0, 0, image::Rgb([200, 30, 40]));
+        surface.img.put_pixel(1, 0, image::Rgb([60, 60, 60]));
+        surface.img.put_pixel(2, 0, image::Rgb([230, 220, 240]));
+        surface.quantize(Dithering::Threshold);
+        assert_eq!(image::Rgb([255, 0, 0]), *surface.img.get_pixel(0, 0));
+        assert_eq!(image::Rgb([0, 0, 0]), *surface.img.get_pixel(1, 0));
+        assert_eq!(image::Rgb([255, 255, 255]), *surface.img.get_pixel(2, 0));
+    }
+
+    #[test]
+    fn test_text_draws_in_accent_color() {
+        let mut surface = Surface::new(100, 40).unwrap();
+        surface.palette = Palette::BlackWhiteRed;
+        let mut text = Text::new(String::from("Hamburg"), 13.0, "Roboto");
+        text.color = Color::Accent;
+        let bounds = surface.bounds();
+        text.draw(&mut surface, 0, 0, bounds);
+        assert!(surface.img.pixels().any(|p| *p == image::Rgb([255, 0, 0])));
+        assert!(!surface.img.pixels().any(|p| *p == image::Rgb([0, 0, 0])));
+    }
+
+    fn is_black(surface: &Surface, x: u32, y: u32) -> bool {
+        *surface.img.get_pixel(x, y) == image::Rgb([0, 0, 0])
+    }
+
+    #[test]
+    fn test_fixed_rectangle_has_its_own_size_plus_padding() {
+        let surface = Surface::new(100, 100).unwrap();
+        let mut rect = Rectangle::new(20, 10);
+        rect.padding(Edge::Left, 5);
+        rect.padding(Edge::Bottom, 3);
+        let bounds = surface.bounds();
+        assert_eq!(Bounds::new(25, 13), rect.bounds(&surface, bounds.zero_hinted()));
+        assert_eq!(Bounds::new(25, 13), rect.bounds(&surface, bounds.infinitely_hinted()));
+    }
+
+    #[test]
+    fn test_flexible_rectangle_behaves_like_spacer() {
+        let surface = Surface::new(100, 100).unwrap();
+        let rect = Rectangle::flexible();
+        let bounds = Bounds::new(60, 40);
+        assert_eq!(Bounds::new(0, 0), rect.bounds(&surface, bounds.zero_hinted()));
+        assert_eq!(Bounds::new(60, 40), rect.bounds(&surface, bounds.optimally_hinted()));
+        assert_eq!(Bounds::new(60, 40), rect.bounds(&surface, bounds.infinitely_hinted()));
+    }
+
+    #[test]
+    fn test_rectangle_draws_outline_and_fill() {
+        let mut surface = Surface::new(20, 20).unwrap();
+        surface.palette = Palette::BlackWhiteRed;
+        let mut rect = Rectangle::new(10, 10);
+        rect.style.stroke_width = 2;
+        rect.style.fill = Some(Color::Accent);
+        let bounds = surface.bounds();
+        rect.draw(&mut surface, 5, 5, bounds);
+        assert!(is_black(&surface, 5, 5));
+        assert!(is_black(&surface, 6, 10));
+        assert!(is_black(&surface, 14, 14));
+        assert_eq!(image::Rgb([255, 0, 0]), *surface.img.get_pixel(7, 7));
+        assert_eq!(image::Rgb([255, 255, 255]), *surface.img.get_pixel(15, 15));
+        assert_eq!(image::Rgb([255, 255, 255]), *surface.img.get_pixel(4, 4));
+    }
+
+    #[test]
+    fn test_rounded_rectangle_leaves_corners_empty() {
+        let mut surface = Surface::new(20, 20).unwrap();
+        let mut rect = RoundedRectangle::new(20, 20, 6);
+        rect.style = ShapeStyle::filled(Color::Black);
+        let bounds = surface.bounds();
+        rect.draw(&mut surface, 0, 0, bounds);
+        assert!(!is_black(&surface, 0, 0));
+        assert!(!is_black(&surface, 19, 19));
+        assert!(is_black(&surface, 10, 0));
+        assert!(is_black(&surface, 10, 10));
+    }
+
+    #[test]
+    fn test_flexible_circle_uses_smaller_side() {
+        let mut surface = Surface::new(40, 40).unwrap();
+        let mut circle = Circle::flexible();
+        circle.style = ShapeStyle::filled(Color::Black);
+        let bounds = Bounds::new(40, 20);
+        assert_eq!(Bounds::new(20, 20), circle.bounds(&surface, bounds));
+        circle.draw(&mut surface, 0, 0, bounds);
+        assert!(is_black(&surface, 10, 10));
+        assert!(!is_black(&surface, 1, 1));
+        assert!(!is_black(&surface, 25, 10));
+    }
+
+    #[test]
+    fn test_line_draws_diagonal() {
+        let mut surface = Surface::new(20, 20).unwrap();
+        let line = Line::new((0, 0), (9, 9));
+        let bounds = surface.bounds();
+        assert_eq!(Bounds::new(10, 10), line.bounds(&surface, bounds));
+        line.draw(&mut surface, 2, 2, bounds);
+        for i in 0..10 {
+            assert!(is_black(&surface, 2 + i, 2 + i));
+        }
+        assert!(!is_black(&surface, 3, 2));
+    }
+
+    #[test]
+    fn test_divider_stretches_across_vstack() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(TestView::new(30, 10)));
+        let mut divider = Divider::horizontal();
+        divider.stroke_width = 2;
+        vstack.views.push(Box::new(divider));
+        vstack.views.push(Box::new(TestView::new(50, 10)));
+        let bounds = surface.bounds();
+        assert_eq!(0, Divider::horizontal().bounds(&surface, bounds.zero_hinted()).width);
+        assert_eq!(22, vstack.bounds(&surface, bounds).height);
+        vstack.draw(&mut surface, 0, 0, bounds);
+        assert!(is_black(&surface, 0, 10));
+        assert!(is_black(&surface, 99, 11));
+        assert!(!is_black(&surface, 50, 12));
+    }
+
+    #[test]
+    fn test_vertical_divider_stretches_across_hstack() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::new(10, 30)));
+        hstack.views.push(Box::new(Divider::vertical()));
+        hstack.views.push(Box::new(TestView::new(10, 50)));
+        let bounds = surface.bounds();
+        assert_eq!(21, hstack.bounds(&surface, bounds).width);
+        hstack.draw(&mut surface, 0, 0, bounds);
+        assert!(is_black(&surface, 10, 0));
+        assert!(is_black(&surface, 10, 99));
+        assert!(!is_black(&surface, 11, 50));
+    }
+
+    #[test]
+    fn test_background_paints_behind_padded_child() {
+        let mut surface = Surface::new(50, 50).unwrap();
+        let mut child = Rectangle::new(10, 10);
+        child.style = ShapeStyle::filled(Color::White);
+        child.padding(Edge::Left, 5);
+        child.padding(Edge::Top, 5);
+        let mut background = Background::new(Box::new(child), Color::Black);
+        background.padding(Edge::Left, 3);
+        background.padding(Edge::Top, 3);
+        let bounds = surface.bounds();
+        assert_eq!(Bounds::new(18, 18), background.bounds(&surface, bounds));
+        background.draw(&mut surface, 0, 0, bounds);
+        // Our own padding stays empty.
+        assert!(!is_black(&surface, 2, 2));
+        // The child's padding gets the background.
+        assert!(is_black(&surface, 3, 3));
+        assert!(is_black(&surface, 7, 12));
+        // The child draws on top of the background.
+        assert!(!is_black(&surface, 8, 8));
+        assert!(!is_black(&surface, 18, 18));
+    }
+
+    #[test]
+    fn test_border_surrounds_child() {
+        let mut surface = Surface::new(50, 50).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut border = Border::new(Box::new(TestView::monitored(1, register.clone(), 10, 10)));
+        border.stroke_width = 2;
+        border.padding(Edge::Left, 1);
+        let bounds = surface.bounds();
+        assert_eq!(Bounds::new(15, 14), border.bounds(&surface, bounds));
+        border.draw(&mut surface, 0, 0, bounds);
+        assert!(register.was_drawn_at(1, 3, 2));
+        assert!(register.was_drawn_with_bounds(1, Bounds::new(10, 10)));
+        assert!(!is_black(&surface, 0, 0));
+        assert!(is_black(&surface, 1, 0));
+        assert!(is_black(&surface, 14, 13));
+        assert!(!is_black(&surface, 5, 5));
+    }
+
+    #[test]
+    fn test_invert_flips_grays_but_keeps_accent() {
+        let mut surface = Surface::new(20, 20).unwrap();
+        surface.palette = Palette::BlackWhiteRed;
+        let mut hstack = HStack::new();
+        let mut dot = Rectangle::new(5, 5);
+        dot.style = ShapeStyle::filled(Color::Accent);
+        hstack.views.push(Box::new(dot));
+        hstack.views.push(Box::new(Rectangle::new(5, 5)));
+        let invert = Invert::new(Box::new(hstack));
+        let bounds = surface.bounds();
+        invert.draw(&mut surface, 0, 0, bounds);
+        assert_eq!(image::Rgb([255, 0, 0]), *surface.img.get_pixel(2, 2));
+        // Outline of the second rectangle turns white, its inside black.
+        assert!(!is_black(&surface, 5, 0));
+        assert!(is_black(&surface, 7, 2));
+        // Outside of the frame is untouched.
+        assert!(!is_black(&surface, 12, 12));
+    }
+
+    #[test]
+    fn test_zstack_is_as_large_as_largest_child() {
+        let surface = Surface::new(300, 300).unwrap();
+        let mut zstack = ZStack::new();
+        zstack.push(Box::new(TestView::new(50, 10)), Alignment::TopLeft);
+        zstack.push(Box::new(TestView::new(20, 80)), Alignment::Center);
+        zstack.padding(Edge::Left, 5);
+        zstack.padding(Edge::Bottom, 7);
+        assert_eq!(Bounds::new(55, 87), zstack.bounds(&surface, surface.bounds()));
+    }
+
+    #[test]
+    fn test_zstack_aligns_children() {
+        let mut surface = Surface::new(300, 300).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut zstack = ZStack::new();
+        zstack.push(Box::new(TestView::monitored(1, register.clone(), 100, 60)), Alignment::Center);
+        zstack.push(Box::new(TestView::monitored(2, register.clone(), 10, 10)), Alignment::TopLeft);
+        zstack.push(Box::new(TestView::monitored(3, register.clone(), 10, 10)), Alignment::Center);
+        zstack.push(Box::new(TestView::monitored(4, register.clone(), 10, 10)), Alignment::BottomRight);
+        zstack.push(Box::new(TestView::monitored(5, register.clone(), 10, 10)), Alignment::Top);
+        zstack.push(Box::new(TestView::monitored(6, register.clone(), 10, 10)), Alignment::Left);
+        zstack.padding(Edge::Left, 5);
+        zstack.padding(Edge::Top, 5);
+        let bounds = surface.bounds();
+        zstack.draw(&mut surface, 10, 20, bounds);
+        assert!(register.was_drawn_at(1, 15, 25));
+        assert!(register.was_drawn_at(2, 15, 25));
+        assert!(register.was_drawn_at(3, 60, 50));
+        assert!(register.was_drawn_at(4, 105, 75));
+        assert!(register.was_drawn_at(5, 60, 25));
+        assert!(register.was_drawn_at(6, 15, 50));
+    }
+
+    #[test]
+    fn test_zstack_stretches_flexible_children_to_frame() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        let mut zstack = ZStack::new();
+        let mut background = Rectangle::flexible();
+        background.style = ShapeStyle::filled(Color::Black);
+        zstack.push(Box::new(background), Alignment::Center);
+        zstack.push(Box::new(TestView::new(30, 20)), Alignment::Center);
+        let bounds = Bounds::new(100, 100);
+        assert_eq!(Bounds::new(30, 20), zstack.bounds(&surface, bounds.zero_hinted()));
+        assert_eq!(Bounds::new(100, 100), zstack.bounds(&surface, bounds.optimally_hinted()));
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(zstack));
+        vstack.views.push(Box::new(TestView::new(30, 60)));
+        vstack.draw(&mut surface, 0, 0, bounds);
+        // The ZStack gets the 40 pixels left over by the other view.
+        assert!(is_black(&surface, 99, 39));
+        assert!(!is_black(&surface, 0, 40));
+    }
+
+    #[test]
+    fn test_grid_sizes_fixed_fractional_and_content_tracks() {
+        let surface = Surface::new(300, 300).unwrap();
+        let mut grid = Grid::new(
+            vec![Track::Fixed(20), Track::Content, Track::Fraction(1), Track::Fraction(2)],
+            vec![Track::Content],
+        );
+        grid.cells.push(GridCell::new(Box::new(TestView::new(35, 10)), 0, 1));
+        grid.cells.push(GridCell::new(Box::new(TestView::new(15, 12)), 0, 1));
+        grid.column_spacing = 5;
+        let (columns, rows) = grid.track_sizes(&surface, Bounds::new(200, 100));
+        // 200 - 3 * 5 (spacing) - 20 (fixed) - 35 (content) = 130 left for
+        // the fractional columns.
+        assert_eq!(vec![20, 35, 43, 87], columns);
+        assert_eq!(vec![12], rows);
+        assert_eq!(Bounds::new(200, 12), grid.bounds(&surface, Bounds::new(200, 100)));
+    }
+
+    #[test]
+    fn test_grid_collapses_fractional_tracks_when_zero_hinted() {
+        let surface = Surface::new(300, 300).unwrap();
+        let mut grid = Grid::new(
+            vec![Track::Fraction(1), Track::Fraction(1)],
+            vec![Track::Fraction(1)],
+        );
+        grid.cells.push(GridCell::new(Box::new(TestView::new(30, 10)), 0, 0));
+        let bounds = Bounds::new(200, 100);
+        assert_eq!(Bounds::new(30, 10), grid.bounds(&surface, bounds.zero_hinted()));
+        assert_eq!(Bounds::new(200, 100), grid.bounds(&surface, bounds.optimally_hinted()));
+    }
+
+    #[test]
+    fn test_grid_spanning_cell_grows_content_tracks() {
+        let surface = Surface::new(300, 300).unwrap();
+        let mut grid = Grid::new(
+            vec![Track::Content, Track::Content],
+            vec![Track::Content, Track::Content],
+        );
+        grid.cells.push(GridCell::new(Box::new(TestView::new(10, 10)), 0, 0));
+        grid.cells.push(GridCell::new(Box::new(TestView::new(10, 10)), 0, 1));
+        let mut wide = GridCell::new(Box::new(TestView::new(42, 10)), 1, 0);
+        wide.column_span = 2;
+        grid.cells.push(wide);
+        grid.column_spacing = 5;
+        let (columns, _) = grid.track_sizes(&surface, Bounds::new(300, 300));
+        // The wide view needs 42 - (10 + 5 + 10) = 17 more pixels. The first
+        // column gets the odd pixel.
+        assert_eq!(vec![19, 18], columns);
+    }
+
+    #[test]
+    fn test_grid_lines_up_columns_across_rows() {
+        let mut surface = Surface::new(300, 300).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let mut grid = Grid::new(
+            vec![Track::Content, Track::Fraction(1), Track::Fraction(1)],
+            vec![Track::Content, Track::Content],
+        );
+        grid.cells.push(GridCell::new(Box::new(TestView::monitored(1, register.clone(), 40, 10)), 0, 0));
+        grid.cells.push(GridCell::new(Box::new(TestView::monitored(2, register.clone(), 20, 10)), 1, 0));
+        grid.cells.push(GridCell::new(Box::new(TestView::monitored(3, register.clone(), 10, 10)), 0, 1));
+        grid.cells.push(GridCell::new(Box::new(TestView::monitored(4, register.clone(), 30, 20)), 1, 1));
+        let mut right = GridCell::new(Box::new(TestView::monitored(5, register.clone(), 10, 10)), 0, 2);
+        right.align = Alignment::BottomRight;
+        grid.cells.push(right);
+        let mut centered = GridCell::new(Box::new(TestView::monitored(6, register.clone(), 10, 10)), 1, 2);
+        centered.align = Alignment::Center;
+        grid.cells.push(centered);
+        // Out of bounds, must be ignored.
+        grid.cells.push(GridCell::new(Box::new(TestView::monitored(7, register.clone(), 10, 10)), 5, 5));
+        grid.padding(Edge::Left, 10);
+        grid.padding(Edge::Right, 10);
+        grid.row_spacing = 2;
+        let bounds = Bounds::new(200, 100);
+        grid.draw(&mut surface, 0, 0, bounds);
+        // Columns: 40 (content), then (200 - 20 - 40) / 2 = 70 each.
+        assert!(register.was_drawn_at(1, 10, 0));
+        assert!(register.was_drawn_at(2, 10, 12));
+        assert!(register.was_drawn_at(3, 50, 0));
+        assert!(register.was_drawn_at(4, 50, 12));
+        assert!(register.was_drawn_at(5, 180, 0));
+        assert!(register.was_drawn_at(6, 150, 17));
+        assert!(!register.was_drawn_at(7, 0, 0));
+        assert_eq!(6, register.drawings.borrow().len());
+    }
+
+    fn two_tone_image() -> Image {
+        // 200x100, left half black, right half white.
+        Image::new(RgbaImage::from_fn(200, 100, |x, _| {
+            if x < 100 { image::Rgba([0, 0, 0, 255]) } else { image::Rgba([255, 255, 255, 255]) }
+        }))
+    }
+
+    #[test]
+    fn test_native_image_ignores_suggested_bounds() {
+        let surface = Surface::new(100, 100).unwrap();
+        let mut image = two_tone_image();
+        image.padding(Edge::Top, 4);
+        let bounds = Bounds::new(50, 50);
+        assert_eq!(Bounds::new(200, 104), image.bounds(&surface, bounds.zero_hinted()));
+        assert_eq!(Bounds::new(200, 104), image.bounds(&surface, bounds.optimally_hinted()));
+    }
+
+    #[test]
+    fn test_scaled_images_are_flexible() {
+        let surface = Surface::new(100, 100).unwrap();
+        let mut image = two_tone_image();
+        let bounds = Bounds::new(150, 150);
+        image.content_mode = ContentMode::Fit;
+        assert_eq!(Bounds::new(0, 0), image.bounds(&surface, bounds.zero_hinted()));
+        assert_eq!(Bounds::new(150, 75), image.bounds(&surface, bounds.optimally_hinted()));
+        assert_eq!(Bounds::new(300, 150), image.bounds(&surface, Bounds::new(400, 150)));
+        image.content_mode = ContentMode::Fill;
+        assert_eq!(Bounds::new(150, 150), image.bounds(&surface, bounds.infinitely_hinted()));
+        image.content_mode = ContentMode::Stretch;
+        assert_eq!(Bounds::new(150, 150), image.bounds(&surface, bounds.optimally_hinted()));
+    }
+
+    #[test]
+    fn test_fit_image_draws_within_bounds() {
+        let mut surface = Surface::new(152, 152).unwrap();
+        let mut image = two_tone_image();
+        image.content_mode = ContentMode::Fit;
+        image.filter = FilterType::Nearest;
+        image.padding(Edge::Top, 2);
+        let bounds = surface.bounds();
+        image.draw(&mut surface, 0, 0, bounds);
+        assert!(is_black(&surface, 0, 2));
+        assert!(is_black(&surface, 74, 76));
+        assert!(!is_black(&surface, 77, 76));
+        assert!(!is_black(&surface, 0, 1));
+        assert!(!is_black(&surface, 0, 78));
+    }
+
+    #[test]
+    fn test_fill_image_crops_center() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        let mut image = two_tone_image();
+        image.content_mode = ContentMode::Fill;
+        image.filter = FilterType::Nearest;
+        let bounds = Bounds::new(50, 50);
+        image.draw(&mut surface, 0, 0, bounds);
+        // Scaled to 100x50, the center 50x50 is half black, half white.
+        assert!(is_black(&surface, 0, 0));
+        assert!(is_black(&surface, 24, 49));
+        assert!(!is_black(&surface, 25, 0));
+        assert!(!is_black(&surface, 0, 50));
+    }
+
+    #[test]
+    fn test_oversized_native_image_does_not_panic() {
+        let mut surface = Surface::new(152, 152).unwrap();
+        let image = two_tone_image();
+        let bounds = surface.bounds();
+        image.draw(&mut surface, 100, 100, bounds);
+        assert!(is_black(&surface, 151, 151));
+    }
+
+    fn encode(img: image::DynamicImage, format: ImageFormat) -> std::io::Cursor<Vec<u8>> {
+        let mut data = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut data, format).unwrap();
+        data.set_position(0);
+        data
+    }
+
+    #[test]
+    fn test_image_sniffs_format() {
+        let pixels = RgbImage::from_pixel(8, 4, image::Rgb([0, 0, 0]));
+        for format in [ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::Gif, ImageFormat::Bmp, ImageFormat::WebP] {
+            let data = encode(image::DynamicImage::ImageRgb8(pixels.clone()), format);
+            let image = Image::from_data(data).unwrap();
+            assert_eq!((8, 4), image.image_data.dimensions(), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_image_rejects_garbage() {
+        let data = std::io::Cursor::new(b"definitely not an image".to_vec());
+        assert!(Image::from_data(data).is_err());
+    }
+
+    #[test]
+    fn test_image_alpha_composites_onto_surface() {
+        let mut surface = Surface::new(3, 1).unwrap();
+        surface.img.put_pixel(0, 0, image::Rgb([255, 0, 0]));
+        let pixels = RgbaImage::from_fn(3, 1, |x, _| match x {
+            0 => image::Rgba([0, 0, 0, 0]),
+            1 => image::Rgba([0, 0, 0, 128]),
+            _ => image::Rgba([0, 0, 0, 255]),
+        });
+        let data = encode(image::DynamicImage::ImageRgba8(pixels), ImageFormat::Png);
+        let image = Image::from_data(data).unwrap();
+        let bounds = surface.bounds();
+        image.draw(&mut surface, 0, 0, bounds);
+        assert_eq!(image::Rgb([255, 0, 0]), *surface.img.get_pixel(0, 0));
+        assert_eq!(image::Rgb([127, 127, 127]), *surface.img.get_pixel(1, 0));
+        assert_eq!(image::Rgb([0, 0, 0]), *surface.img.get_pixel(2, 0));
+    }
+
+    // A 10x10 SVG with a black square in the left half.
+    const HALF_SQUARE_SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10" viewBox="0 0 10 10">
+        <rect x="0" y="0" width="5" height="10" fill="black"/>
+    </svg>"#;
+
+    #[test]
+    fn test_svg_image_native_size() {
+        let surface = Surface::new(100, 100).unwrap();
+        let mut svg = SvgImage::from_data(HALF_SQUARE_SVG.as_bytes()).unwrap();
+        svg.content_mode = ContentMode::Native;
+        assert_eq!(Bounds::new(10, 10), svg.bounds(&surface, surface.bounds()));
+    }
+
+    #[test]
+    fn test_svg_image_fits_by_default() {
+        let surface = Surface::new(100, 100).unwrap();
+        let svg = SvgImage::from_data(HALF_SQUARE_SVG.as_bytes()).unwrap();
+        assert_eq!(Bounds::new(40, 40), svg.bounds(&surface, Bounds::new(80, 40)));
+        assert_eq!(Bounds::new(0, 0), svg.bounds(&surface, Bounds::new(80, 40).zero_hinted()));
+    }
+
+    #[test]
+    fn test_svg_image_is_rasterized_at_assigned_size() {
+        let mut surface = Surface::new(60, 30).unwrap();
+        let svg = SvgImage::from_data(HALF_SQUARE_SVG.as_bytes()).unwrap();
+        let bounds = surface.bounds();
+        svg.draw(&mut surface, 0, 0, bounds);
+        // Fit into 60x30 gives a 30x30 drawing, the black half covers x 0..15.
+        assert!(is_black(&surface, 0, 0));
+        assert!(is_black(&surface, 14, 29));
+        assert!(!is_black(&surface, 16, 15));
+        // Transparent parts leave the surface alone.
+        assert_eq!(image::Rgb([255, 255, 255]), *surface.img.get_pixel(45, 15));
+    }
+
+    #[test]
+    fn test_svg_image_stretch() {
+        let mut surface = Surface::new(60, 30).unwrap();
+        let mut svg = SvgImage::from_data(HALF_SQUARE_SVG.as_bytes()).unwrap();
+        svg.content_mode = ContentMode::Stretch;
+        let bounds = surface.bounds();
+        assert_eq!(Bounds::new(60, 30), svg.bounds(&surface, bounds));
+        svg.draw(&mut surface, 0, 0, bounds);
+        assert!(is_black(&surface, 29, 15));
+        assert!(!is_black(&surface, 31, 15));
+    }
+
+    #[test]
+    fn test_svg_image_rejects_garbage() {
+        assert!(SvgImage::from_data("<svg".as_bytes()).is_err());
+    }
+
+    /// The (start, end) of the inked part of every line, using `\n` as the line
+    /// separator in `text`.
+    fn line_extents(text: &Text, surface: &Surface, bounds: Bounds) -> Vec<(f32, f32)> {
+        let glyphs = text.glyph_positions(surface, bounds);
+        let mut extents = Vec::new();
+        let mut current: Option<(f32, f32)> = None;
+        for glyph in glyphs {
+            if glyph.parent == '\n' {
+                extents.extend(current.take());
+                continue;
+            }
+            if glyph.char_data.is_whitespace() {
+                continue;
+            }
+            let (start, end) = current.unwrap_or((f32::MAX, 0.0));
+            current = Some((start.min(glyph.x), end.max(glyph.x + glyph.width as f32)));
+        }
+        extents.extend(current);
+        extents
+    }
+
+    #[test]
+    fn test_text_alignment_is_relative_to_widest_line() {
+        let surface = Surface::new(296, 128).unwrap();
+        let mut text = Text::new(String::from("Hi\nHamburg Altona"), 13.0, "Roboto");
+        let bounds = surface.bounds();
+
+        let left = line_extents(&text, &surface, bounds);
+        assert!(left[0].0 < 2.0);
+
+        text.alignment = TextAlignment::Right;
+        let right = line_extents(&text, &surface, bounds);
+        assert_eq!(right[0].1, right[1].1);
+
+        text.alignment = TextAlignment::Center;
+        let center = line_extents(&text, &surface, bounds);
+        let short_middle = (center[0].0 + center[0].1) / 2.0;
+        let long_middle = (center[1].0 + center[1].1) / 2.0;
+        assert!((short_middle - long_middle).abs() <= 1.0);
+
+        // The view doesn't get any wider because of the alignment.
+        text.alignment = TextAlignment::Left;
+        let left_bounds = text.bounds(&surface, bounds);
+        text.alignment = TextAlignment::Right;
+        assert_eq!(left_bounds, text.bounds(&surface, bounds));
+    }
+
+    #[test]
+    fn test_justified_text_fills_all_but_the_last_line() {
+        let surface = Surface::new(296, 128).unwrap();
+        let mut text = Text::new(
+            String::from("The quick brown fox jumps over the lazy dog and runs off"),
+            13.0,
+            "Roboto",
+        );
+        text.wrap_text = true;
+        text.alignment = TextAlignment::Justify;
+        let bounds = Bounds::new(120, 128);
+
+        let glyphs = text.glyph_positions(&surface, bounds);
+        let mut lines: Vec<Vec<GlyphPosition>> = Vec::new();
+        let mut last_y = f32::MIN;
+        for glyph in glyphs.into_iter().filter(|g| !g.char_data.is_whitespace()) {
+            // Every glyph of a line starts roughly on the same baseline, so a
+            // big jump in y means a new line.
+            if glyph.y > last_y + 8.0 || lines.is_empty() {
+                lines.push(Vec::new());
+                last_y = glyph.y;
+            }
+            lines.last_mut().unwrap().push(glyph);
+        }
+        assert!(lines.len() > 2);
+
+        let widths: Vec<f32> = lines.iter().map(|l| line_width(l)).collect();
+        let block = widths.iter().copied().fold(0.0, f32::max);
+        for width in &widths[..widths.len() - 1] {
+            assert!(block - width <= 1.0, "{:?}", widths);
+        }
+        assert!(block - widths[widths.len() - 1] > 1.0, "{:?}", widths);
+    }
+
+    #[test]
+    fn test_line_height_spreads_lines() {
+        let surface = Surface::new(296, 128).unwrap();
+        let mut text = Text::new(String::from("Hamburg\nAltona"), 13.0, "Roboto");
+        let normal = text.bounds(&surface, surface.bounds());
+        text.line_height = 2.0;
+        let spread = text.bounds(&surface, surface.bounds());
+        assert_eq!(normal.width, spread.width);
+        assert!(spread.height > normal.height + 10);
+    }
+
+    #[test]
+    fn test_letter_spacing_widens_text() {
+        let surface = Surface::new(296, 128).unwrap();
+        let mut text = Text::new(String::from("Hamburg"), 13.0, "Roboto");
+        let normal = text.bounds(&surface, surface.bounds());
+        text.letter_spacing = 2.0;
+        let spaced = text.bounds(&surface, surface.bounds());
+        // Six gaps between seven letters.
+        assert_eq!(normal.width + 12, spaced.width);
+        assert_eq!(normal.height, spaced.height);
+    }
+
+    /// The text that's actually laid out, after truncation.
+    fn rendered_text(text: &Text, surface: &Surface, bounds: Bounds) -> String {
+        text.glyph_positions(surface, bounds).iter().map(|g| g.parent).collect()
+    }
+
+    fn truncated_headline(truncation: Truncation) -> Text {
+        let mut text = Text::new(
+            String::from("Bundestag beschliesst den Haushalt"),
+            13.0,
+            "Roboto",
+        );
+        text.wrap_text = true;
+        text.max_lines = Some(1);
+        text.truncation = truncation;
+        text
+    }
+
+    #[test]
+    fn test_text_without_max_lines_is_not_truncated() {
+        let surface = Surface::new(296, 128).unwrap();
+        let mut text = truncated_headline(Truncation::Tail);
+        text.max_lines = None;
+        let rendered = rendered_text(&text, &surface, Bounds::new(100, 128));
+        assert_eq!("Bundestag beschliesst den Haushalt", rendered.trim_end());
+    }
+
+    #[test]
+    fn test_text_that_fits_is_not_truncated() {
+        let surface = Surface::new(296, 128).unwrap();
+        let text = truncated_headline(Truncation::Tail);
+        let rendered = rendered_text(&text, &surface, surface.bounds());
+        assert_eq!("Bundestag beschliesst den Haushalt", rendered);
+    }
+
+    #[test]
+    fn test_tail_truncation() {
+        let surface = Surface::new(296, 128).unwrap();
+        let text = truncated_headline(Truncation::Tail);
+        let bounds = Bounds::new(100, 128);
+        let rendered = rendered_text(&text, &surface, bounds);
+        assert!(rendered.starts_with("Bundestag b"), "{}", rendered);
+        assert!(rendered.ends_with('…'), "{}", rendered);
+        assert!(text.bounds(&surface, bounds).width <= 100);
+    }
+
+    #[test]
+    fn test_middle_truncation() {
+        let surface = Surface::new(296, 128).unwrap();
+        let text = truncated_headline(Truncation::Middle);
+        let rendered = rendered_text(&text, &surface, Bounds::new(100, 128));
+        assert!(rendered.starts_with("Bundes"), "{}", rendered);
+        assert!(rendered.ends_with("alt"), "{}", rendered);
+        assert!(rendered.contains('…'), "{}", rendered);
+    }
+
+    #[test]
+    fn test_word_truncation() {
+        let surface = Surface::new(296, 128).unwrap();
+        let text = truncated_headline(Truncation::Word);
+        let rendered = rendered_text(&text, &surface, Bounds::new(100, 128));
+        assert_eq!("Bundestag…", rendered);
+    }
+
+    #[test]
+    fn test_truncation_to_multiple_lines() {
+        let surface = Surface::new(296, 128).unwrap();
+        let mut text = truncated_headline(Truncation::Word);
+        let one_line = text.bounds(&surface, Bounds::new(100, 128));
+        text.max_lines = Some(2);
+        let two_lines = text.bounds(&surface, Bounds::new(100, 128));
+        assert!(two_lines.height > one_line.height);
+        let rendered = rendered_text(&text, &surface, Bounds::new(100, 128));
+        assert!(rendered.ends_with('…'), "{}", rendered);
+        assert!(rendered.len() > "Bundestag…".len());
+    }
+
+    fn fitted_headline(search: FitSearch) -> Text {
+        let mut text = Text::new(
+            String::from("Bundestag beschliesst den Haushalt nach langer Debatte"),
+            13.0,
+            "PlayfairDisplay",
+        );
+        text.wrap_text = true;
+        text.shrink_to_fit = Some(ShrinkToFit { min_size: 10.0, max_size: 40.0, search });
+        text
+    }
+
+    /// The font size the text was laid out with.
+    fn laid_out_size(text: &Text, surface: &Surface, bounds: Bounds) -> f32 {
+        text.glyph_positions(surface, bounds)[0].key.px
+    }
+
+    #[test]
+    fn test_shrink_to_fit_uses_max_size_if_there_is_room() {
+        let surface = Surface::new(296, 128).unwrap();
+        let mut text = fitted_headline(FitSearch::Binary);
+        text.text = String::from("Hi");
+        assert_eq!(40.0, laid_out_size(&text, &surface, surface.bounds()));
+    }
+
+    #[test]
+    fn test_shrink_to_fit_fits_offered_bounds() {
+        let surface = Surface::new(296, 128).unwrap();
+        let offered = Bounds::new(200, 80);
+        for search in [FitSearch::Binary, FitSearch::Step(1.0), FitSearch::Step(0.0)] {
+            let mut text = fitted_headline(search);
+            text.padding(Edge::Left, 5);
+            text.padding(Edge::Top, 5);
+            let bounds = text.bounds(&surface, offered);
+            assert!(bounds.width <= 200 && bounds.height <= 80, "{:?}: {:?}", search, bounds);
+            let size = laid_out_size(&text, &surface, offered);
+            assert!(size > 10.0 && size < 40.0, "{:?}: {}", search, size);
+        }
+    }
+
+    #[test]
+    fn test_shrink_to_fit_search_strategies_agree() {
+        let surface = Surface::new(296, 128).unwrap();
+        let offered = Bounds::new(200, 80);
+        let binary = laid_out_size(&fitted_headline(FitSearch::Binary), &surface, offered);
+        let step = laid_out_size(&fitted_headline(FitSearch::Step(1.0)), &surface, offered);
+        assert!((binary - step).abs() < 1.0, "{} vs. {}", binary, step);
+    }
+
+    #[test]
+    fn test_shrink_to_fit_reuses_the_size_for_the_same_bounds() {
+        let surface = Surface::new(296, 128).unwrap();
+        let text = fitted_headline(FitSearch::Step(1.0));
+        assert_ne!(12.0, laid_out_size(&text, &surface, Bounds::new(200, 80)));
+        // A size the search would never pick, to see whether it's searched
+        // again.
+        text.fitted_size.borrow_mut().as_mut().unwrap().size = 12.0;
+        assert_eq!(12.0, laid_out_size(&text, &surface, Bounds::new(200, 80)));
+        assert_ne!(12.0, laid_out_size(&text, &surface, Bounds::new(296, 128)));
+    }
+
+    #[test]
+    fn test_shrink_to_fit_truncates_below_min_size() {
+        let surface = Surface::new(296, 128).unwrap();
+        let mut text = fitted_headline(FitSearch::Binary);
+        text.shrink_to_fit = Some(ShrinkToFit { min_size: 20.0, max_size: 40.0, search: FitSearch::Binary });
+        let offered = Bounds::new(120, 60);
+        let bounds = text.bounds(&surface, offered);
+        assert!(bounds.width <= 120 && bounds.height <= 60, "{:?}", bounds);
+        assert_eq!(20.0, laid_out_size(&text, &surface, offered));
+        let rendered = rendered_text(&text, &surface, offered);
+        assert!(rendered.ends_with('…'), "{}", rendered);
+    }
+
+    #[test]
+    fn test_shrink_to_fit_uses_space_offered_by_parent() {
+        let surface = Surface::new(296, 128).unwrap();
+        let mut grid = Grid::new(vec![Track::Fraction(1)], vec![Track::Fraction(1), Track::Fixed(30)]);
+        grid.cells.push(GridCell::new(Box::new(fitted_headline(FitSearch::Binary)), 0, 0));
+        grid.cells.push(GridCell::new(Box::new(TestView::new(296, 30)), 1, 0));
+        let mut surface = surface;
+        let bounds = surface.bounds();
+        grid.draw(&mut surface, 0, 0, bounds);
+
+        // The headline grows into the 98 pixels left by the bottom row, but
+        // doesn't draw into it.
+        let lowest_ink = (0..128)
+            .filter(|&y| (0..296).any(|x| is_black(&surface, x, y)))
+            .max()
+            .unwrap();
+        assert!(lowest_ink > 60 && lowest_ink < 98, "{}", lowest_ink);
+    }
+
+    fn text_bounds(surface: &Surface, font: &str) -> Bounds {
+        Text::new(String::from("Hamburg"), 13.0, font).bounds(surface, surface.bounds())
+    }
+
+    #[test]
+    fn test_unknown_font_falls_back_to_default() {
+        let surface = Surface::new(296, 128).unwrap();
+        assert!(surface.load_font("Comic Sans").is_err());
+        assert_eq!(text_bounds(&surface, DEFAULT_FONT), text_bounds(&surface, "Comic Sans"));
+    }
+
+    #[test]
+    fn test_register_font_bytes() {
+        let mut surface = Surface::new(296, 128).unwrap();
+        let data = fs::read(Surface::font_path("PlayfairDisplay-Regular.ttf").unwrap()).unwrap();
+        surface.register_font_bytes("Serif", data);
+        surface.load_font("Serif").unwrap();
+        assert_eq!(text_bounds(&surface, "PlayfairDisplay"), text_bounds(&surface, "Serif"));
+        assert_ne!(text_bounds(&surface, DEFAULT_FONT), text_bounds(&surface, "Serif"));
+    }
+
+    #[test]
+    fn test_register_font_dir() {
+        let mut surface = Surface::new(296, 128).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        fs::copy(
+            Surface::font_path("PlayfairDisplay-Regular.ttf").unwrap(),
+            dir.path().join("Playfair.TTF"),
+        ).unwrap();
+        fs::write(dir.path().join("README.txt"), "not a font").unwrap();
+
+        assert_eq!(1, surface.register_font_dir(dir.path()).unwrap());
+        surface.load_font("Playfair").unwrap();
+        assert!(surface.load_font("README").is_err());
+        assert!(surface.register_font_dir(dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_fonts_are_loaded_lazily() {
+        let mut surface = Surface::new(296, 128).unwrap();
+        // Registering a file that doesn't exist is fine until the font is
+        // used.
+        surface.register_font_file("Missing", "/does/not/exist.ttf");
+        assert!(surface.load_font("Missing").is_err());
+        assert_eq!(text_bounds(&surface, DEFAULT_FONT), text_bounds(&surface, "Missing"));
+
+        // Re-registering a name replaces the font.
+        surface.register_font_file("Missing", Surface::font_path("RobotoMono-Regular.ttf").unwrap());
+        surface.load_font("Missing").unwrap();
+        assert_eq!(text_bounds(&surface, "RobotoMono"), text_bounds(&surface, "Missing"));
+    }
+
+    #[test]
+    fn test_missing_glyphs_use_fallback_font() {
+        let surface = Surface::new(296, 128).unwrap();
+        let playfair = surface.fonts.index("PlayfairDisplay");
+        let mono = surface.fonts.index("RobotoMono");
+        // RobotoMono doesn't have an arrow, PlayfairDisplay does.
+        let mut text = Text::new(String::from("A → B"), 13.0, "RobotoMono");
+        let fonts = |text: &Text| -> Vec<(char, usize)> {
+            text.glyph_positions(&surface, surface.bounds())
+                .iter()
+                .filter(|g| !g.char_data.is_whitespace())
+                .map(|g| (g.parent, g.font_index))
+                .collect()
+        };
+        assert_eq!(vec![('A', mono), ('→', mono), ('B', mono)], fonts(&text));
+
+        text.fallback_fonts = vec![String::from("Roboto"), String::from("PlayfairDisplay")];
+        assert_eq!(vec![('A', mono), ('→', playfair), ('B', mono)], fonts(&text));
+    }
+
+    #[test]
+    fn test_surface_fallback_fonts_come_last() {
+        let mut surface = Surface::new(296, 128).unwrap();
+        surface.fallback_fonts = vec![String::from("PlayfairDisplay")];
+        let playfair = surface.fonts.index("PlayfairDisplay");
+        let text = Text::new(String::from("→"), 13.0, "RobotoMono");
+        let glyphs = text.glyph_positions(&surface, surface.bounds());
+        assert_eq!(playfair, glyphs[0].font_index);
+
+        // Fallback glyphs are actually drawn.
+        let bounds = surface.bounds();
+        text.draw(&mut surface, 0, 0, bounds);
+        assert!(surface.img.pixels().any(|p| *p == image::Rgb([0, 0, 0])));
+    }
+
+    #[test]
+    fn test_rich_text_measures_like_text_for_a_single_span() {
+        let surface = Surface::new(296, 128).unwrap();
+        let mut text = Text::new(String::from("Bahn: 5 min late"), 13.0, "Roboto");
+        let mut rich = RichText::new(vec![Span::new("Bahn: "), Span::new("5 min late")], 13.0, "Roboto");
+        assert_eq!(text.bounds(&surface, surface.bounds()), rich.bounds(&surface, surface.bounds()));
+
+        text.wrap_text = true;
+        rich.wrap_text = true;
+        let narrow = Bounds::new(60, 128);
+        assert_eq!(text.bounds(&surface, narrow), rich.bounds(&surface, narrow));
+    }
+
+    #[test]
+    fn test_rich_text_spans_have_their_own_style() {
+        let surface = Surface::new(296, 128).unwrap();
+        let mut label = Span::new("Bahn: ");
+        label.font = Some(String::from("PlayfairDisplay"));
+        let mut delay = Span::new("5 min");
+        delay.size = Some(26.0);
+        let rich = RichText::new(vec![label, delay], 13.0, "Roboto");
+
+        let glyphs = rich.glyph_positions(&surface, surface.bounds());
+        let playfair = surface.fonts.index("PlayfairDisplay");
+        let roboto = surface.fonts.index("Roboto");
+        assert!(glyphs.iter().filter(|g| g.user_data == 0).all(|g| g.font_index == playfair && g.key.px == 13.0));
+        assert!(glyphs.iter().filter(|g| g.user_data == 1).all(|g| g.font_index == roboto && g.key.px == 26.0));
+
+        // The bigger span makes the line taller than plain 13px text.
+        let plain = Text::new(String::from("Bahn: 5 min"), 13.0, "Roboto");
+        assert!(rich.bounds(&surface, surface.bounds()).height > plain.bounds(&surface, surface.bounds()).height);
+    }
+
+    #[test]
+    fn test_rich_text_wraps_across_spans() {
+        let surface = Surface::new(296, 128).unwrap();
+        let mut rich = RichText::new(
+            vec![Span::new("Bahn: "), Span::new("Regionalexpress fällt heute aus")],
+            13.0,
+            "Roboto",
+        );
+        let single_line = rich.bounds(&surface, surface.bounds());
+        rich.wrap_text = true;
+        let wrapped = rich.bounds(&surface, Bounds::new(100, 128));
+        assert!(wrapped.width <= 100);
+        assert!(wrapped.height > single_line.height);
+    }
+
+    #[test]
+    fn test_rich_text_span_colors() {
+        let mut surface = Surface::new(100, 30).unwrap();
+        surface.palette = Palette::BlackWhiteRed;
+        let mut late = Span::new("late");
+        late.color = Some(Color::Accent);
+        let rich = RichText::new(vec![Span::new("Bahn "), late], 13.0, "Roboto");
+        let bounds = surface.bounds();
+        rich.draw(&mut surface, 0, 0, bounds);
+        assert!(surface.img.pixels().any(|p| *p == image::Rgb([0, 0, 0])));
+        assert!(surface.img.pixels().any(|p| *p == image::Rgb([255, 0, 0])));
+    }
+
+    #[test]
+    fn test_rotate_swaps_bounds() {
+        let surface = Surface::new(100, 100).unwrap();
+        let mut rotated = Rotate::new(Box::new(TestView::new(40, 10)), Rotation::Clockwise90);
+        assert_eq!(Bounds::new(10, 40), rotated.bounds(&surface, surface.bounds()));
+        rotated.rotation = Rotation::Clockwise180;
+        assert_eq!(Bounds::new(40, 10), rotated.bounds(&surface, surface.bounds()));
+        rotated.rotation = Rotation::Clockwise270;
+        rotated.padding(Edge::Left, 5);
+        assert_eq!(Bounds::new(15, 40), rotated.bounds(&surface, surface.bounds()));
+    }
+
+    #[test]
+    fn test_rotate_offers_child_transposed_bounds() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        let register = Rc::new(DrawingRegister::new());
+        let child = TestView::monitored(1, register.clone(), 40, 10);
+        let rotated = Rotate::new(Box::new(child), Rotation::Clockwise90);
+        rotated.draw(&mut surface, 0, 0, Bounds::new(30, 80));
+        // The child is drawn onto the scratch image, at the origin.
+        assert!(register.was_drawn_with_bounds(1, Bounds::new(40, 10)));
+    }
+
+    #[test]
+    fn test_rotate_moves_pixels() {
+        // A 4x2 rectangle with a black left half, i.e. a 2x2 black square at
+        // the origin.
+        let mut surface = Surface::new(10, 10).unwrap();
+        let mut image = Image::new(RgbaImage::from_fn(4, 2, |x, _| {
+            if x < 2 { image::Rgba([0, 0, 0, 255]) } else { image::Rgba([255, 255, 255, 255]) }
+        }));
+        image.content_mode = ContentMode::Native;
+
+        // Rotated clockwise, the black half ends up at the top.
+        let rotated = Rotate::new(Box::new(image), Rotation::Clockwise90);
+        assert_eq!(Bounds::new(2, 4), rotated.bounds(&surface, surface.bounds()));
+        rotated.draw(&mut surface, 3, 3, Bounds::new(2, 4));
+        assert!(is_black(&surface, 3, 3) && is_black(&surface, 4, 4));
+        assert!(!is_black(&surface, 3, 5) && !is_black(&surface, 4, 6));
+        assert!(!is_black(&surface, 2, 3) && !is_black(&surface, 5, 3));
+    }
+
+    #[test]
+    fn test_rotated_text_in_stack() {
+        let surface = Surface::new(296, 128).unwrap();
+        let text = Text::new(String::from("Hamburg"), 13.0, "Roboto");
+        let text_bounds = text.bounds(&surface, surface.bounds());
+        let mut stack = HStack::new();
+        stack.views.push(Box::new(Rotate::new(Box::new(text), Rotation::Clockwise270)));
+        stack.views.push(Box::new(TestView::new(20, 20)));
+        let bounds = stack.bounds(&surface, surface.bounds());
+        assert_eq!(text_bounds.height + 20, bounds.width);
+        assert_eq!(text_bounds.width, bounds.height);
+
+        let mut surface = surface;
+        let full = surface.bounds();
+        stack.draw(&mut surface, 0, 0, full);
+        // All ink is within the rotated text's column.
+        for (x, _, pixel) in surface.img.enumerate_pixels() {
+            if *pixel != image::Rgb([255, 255, 255]) {
+                assert!(x < text_bounds.height);
+            }
+        }
+    }
+
+    /// A 3x2 surface with a black pixel in the top left corner.
+    fn corner_surface() -> Surface {
+        let mut surface = Surface::new(3, 2).unwrap();
+        surface.set_pixel(0, 0, image::Rgb([0, 0, 0]));
+        surface
+    }
+
+    #[test]
+    fn test_orient_surface() {
+        let mut surface = corner_surface();
+        surface.orient(Rotation::None, false);
+        assert_eq!(Bounds::new(3, 2), surface.bounds());
+        assert!(is_black(&surface, 0, 0));
+
+        let mut surface = corner_surface();
+        surface.orient(Rotation::Clockwise90, false);
+        assert_eq!(Bounds::new(2, 3), surface.bounds());
+        assert!(is_black(&surface, 0, 2));
+
+        let mut surface = corner_surface();
+        surface.orient(Rotation::Clockwise180, false);
+        assert!(is_black(&surface, 2, 1));
+
+        let mut surface = corner_surface();
+        surface.orient(Rotation::Clockwise270, false);
+        assert_eq!(Bounds::new(2, 3), surface.bounds());
+        assert!(is_black(&surface, 1, 0));
+    }
+
+    #[test]
+    fn test_orient_undoes_how_the_tag_is_mounted() {
+        let mut logical = Surface::new(3, 2).unwrap();
+        logical.set_pixel(0, 0, image::Rgb([0, 0, 0]));
+        logical.set_pixel(1, 0, image::Rgb([255, 0, 0]));
+        logical.set_pixel(2, 1, image::Rgb([0, 0, 255]));
+
+        for rotation in [Rotation::None, Rotation::Clockwise90, Rotation::Clockwise180, Rotation::Clockwise270] {
+            for mirror in [false, true] {
+                let mut surface = Surface::new(3, 2).unwrap();
+                surface.img = logical.img.clone();
+                surface.orient(rotation, mirror);
+
+                // What someone looking at the mounted tag sees: the panel's
+                // image turned clockwise, then mirrored.
+                let mut seen = match rotation {
+                    Rotation::None => surface.img.clone(),
+                    Rotation::Clockwise90 => imageops::rotate90(&surface.img),
+                    Rotation::Clockwise180 => imageops::rotate180(&surface.img),
+                    Rotation::Clockwise270 => imageops::rotate270(&surface.img),
+                };
+                if mirror {
+                    imageops::flip_horizontal_in_place(&mut seen);
+                }
+                assert_eq!(logical.img, seen, "{:?}, mirror: {}", rotation, mirror);
+            }
+        }
+    }
+
+    #[test]
+    fn test_orient_mirrors_before_rotating() {
+        let mut surface = corner_surface();
+        surface.orient(Rotation::None, true);
+        assert!(is_black(&surface, 2, 0));
+
+        let mut surface = corner_surface();
+        surface.orient(Rotation::Clockwise90, true);
+        assert!(is_black(&surface, 0, 0));
+    }
+
+    #[test]
+    fn test_rotation_from_degrees() {
+        assert_eq!(Ok(Rotation::Clockwise270), Rotation::try_from(270));
+        assert!(Rotation::try_from(45).is_err());
+        assert_eq!(Rotation::None, Rotation::default());
+    }
+
+    #[test]
+    fn test_qr_code_uses_the_largest_integer_scale_that_fits() {
+        let surface = Surface::new(200, 200).unwrap();
+        // "Hello" fits into a version 1 code, which is 21 modules wide. With
+        // the quiet zone, that's 29 modules.
+        let code = QrCode::new("Hello", ErrorCorrection::Medium).unwrap();
+        assert_eq!(Bounds::new(29, 29), code.bounds(&surface, Bounds::new(57, 100)));
+        assert_eq!(Bounds::new(58, 58), code.bounds(&surface, Bounds::new(100, 58)));
+        // Never smaller than one pixel per module, not even when zero-hinted.
+        assert_eq!(Bounds::new(29, 29), code.bounds(&surface, Bounds::new(10, 10)));
+        assert_eq!(Bounds::new(29, 29), code.bounds(&surface, Bounds::new(0, 0).zero_hinted()));
+    }
+
+    #[test]
+    fn test_qr_code_with_fixed_scale_and_padding() {
+        let surface = Surface::new(200, 200).unwrap();
+        let mut code = QrCode::new("Hello", ErrorCorrection::Medium).unwrap();
+        code.scale = Some(2);
+        code.quiet_zone = 1;
+        code.padding(Edge::Left, 5);
+        assert_eq!(Bounds::new(51, 46), code.bounds(&surface, Bounds::new(200, 200)));
+    }
+
+    #[test]
+    fn test_qr_code_draws_crisp_modules_inside_the_quiet_zone() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        for pixel in surface.img.pixels_mut() {
+            *pixel = image::Rgb([0, 0, 0]);
+        }
+        let code = QrCode::new("Hello", ErrorCorrection::High).unwrap();
+        code.draw(&mut surface, 0, 0, Bounds::new(87, 87));
+
+        // The quiet zone is painted white, even on a black background.
+        assert!((0..87).all(|i| !is_black(&surface, i, 0) && !is_black(&surface, 0, i)));
+        assert!((0..12).all(|i| !is_black(&surface, 11, i)));
+        // The finder pattern in the top left corner starts right after the
+        // quiet zone, with a 3x3 pixel module.
+        assert!(is_black(&surface, 12, 12) && is_black(&surface, 14, 14));
+        // Its second ring is light.
+        assert!(!is_black(&surface, 15, 15));
+        // Nothing outside of the code is touched.
+        assert!(is_black(&surface, 87, 87));
+    }
+
+    #[test]
+    fn test_qr_code_rejects_data_that_is_too_long() {
+        let data = "x".repeat(4000);
+        assert!(QrCode::new(&data, ErrorCorrection::High).is_err());
+    }
+
+    #[test]
+    fn test_barcode_uses_whole_pixel_modules() {
+        let surface = Surface::new(400, 200).unwrap();
+        // EAN-13 is 95 modules wide, plus 11 modules of quiet zone on each
+        // side.
+        let mut code = Barcode::new(Symbology::Ean13, "4006381333931").unwrap();
+        code.text = None;
+        assert_eq!(Bounds::new(117, 50), code.bounds(&surface, Bounds::new(233, 50)));
+        assert_eq!(Bounds::new(234, 50), code.bounds(&surface, Bounds::new(234, 50)));
+        // The bars flex vertically, but the modules never get narrower than a
+        // pixel.
+        assert_eq!(Bounds::new(117, 0), code.bounds(&surface, Bounds::new(0, 0).zero_hinted()));
+
+        code.module_width = Some(3);
+        code.bar_height = Some(20);
+        assert_eq!(Bounds::new(351, 20), code.bounds(&surface, Bounds::new(100, 100)));
+    }
+
+    #[test]
+    fn test_barcode_text_goes_below_the_bars() {
+        let surface = Surface::new(400, 200).unwrap();
+        let code = Barcode::new(Symbology::Code128, "ABC").unwrap();
+        let text = Text::new(String::from("ABC"), 12.0, DEFAULT_FONT);
+        let text_height = text.bounds(&surface, Bounds::new(400, 999)).height;
+
+        let bounds = code.bounds(&surface, Bounds::new(400, 60));
+        assert_eq!(60, bounds.height);
+        assert!(text_height > 0);
+
+        let mut fixed = Barcode::new(Symbology::Code128, "ABC").unwrap();
+        fixed.bar_height = Some(30);
+        assert_eq!(30 + BARCODE_TEXT_SPACING + text_height, fixed.bounds(&surface, Bounds::new(400, 60)).height);
+    }
+
+    #[test]
+    fn test_barcode_draws_bars_after_the_quiet_zone() {
+        let mut surface = Surface::new(300, 100).unwrap();
+        for pixel in surface.img.pixels_mut() {
+            *pixel = image::Rgb([0, 0, 0]);
+        }
+        let mut code = Barcode::new(Symbology::Ean8, "96385074").unwrap();
+        code.text = None;
+        code.module_width = Some(2);
+        code.bar_height = Some(10);
+        code.draw(&mut surface, 0, 0, Bounds::new(300, 100));
+
+        // 7 modules of quiet zone at 2 pixels each, then the start guard: bar,
+        // space, bar.
+        assert!((0..14).all(|x| !is_black(&surface, x, 5)));
+        assert!(is_black(&surface, 14, 0) && is_black(&surface, 15, 9));
+        assert!(!is_black(&surface, 16, 5) && !is_black(&surface, 17, 5));
+        assert!(is_black(&surface, 18, 5));
+        // The trailing quiet zone is white, too, and nothing below the bars is
+        // touched.
+        let width = (67 + 14) * 2;
+        assert!(!is_black(&surface, width - 1, 5));
+        assert!(is_black(&surface, width, 5));
+        assert!(is_black(&surface, 20, 10));
+    }
+
+    #[test]
+    fn test_barcode_rejects_invalid_data() {
+        assert!(Barcode::new(Symbology::Ean13, "4006381333932").is_err());
+        assert!(Barcode::new(Symbology::Code39, "lower case").is_err());
+    }
+
+    #[test]
+    fn test_value_range_covers_values_threshold_and_zero() {
+        let values = [3.0, f32::NAN, 5.0];
+        assert_eq!(ValueRange { min: 3.0, max: 5.0 }, ValueRange::covering(&values, None, false));
+        assert_eq!(ValueRange { min: 3.0, max: 8.0 }, ValueRange::covering(&values, Some(8.0), false));
+        assert_eq!(ValueRange { min: 0.0, max: 5.0 }, ValueRange::covering(&values, None, true));
+        assert_eq!(ValueRange { min: 0.0, max: 0.0 }, ValueRange::covering(&[], None, false));
+    }
+
+    #[test]
+    fn test_charts_are_flexible() {
+        let surface = Surface::new(100, 100).unwrap();
+        let charts: Vec<Box<dyn View>> = vec![
+            Box::new(Sparkline::new(vec![1.0, 2.0])),
+            Box::new(LineChart::new(vec![1.0, 2.0])),
+            Box::new(BarChart::new(vec![1.0, 2.0])),
+        ];
+        for chart in charts {
+            assert_eq!(Bounds::new(80, 30), chart.bounds(&surface, Bounds::new(80, 30)));
+            assert_eq!(Bounds::new(0, 0), chart.bounds(&surface, Bounds::new(0, 0).zero_hinted()));
+        }
+    }
+
+    #[test]
+    fn test_sparkline_scales_to_its_bounds() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        let mut sparkline = Sparkline::new(vec![0.0, 10.0, 5.0]);
+        sparkline.show_min_max = false;
+        sparkline.draw(&mut surface, 10, 10, Bounds::new(21, 11));
+
+        assert!(is_black(&surface, 10, 20));
+        assert!(is_black(&surface, 20, 10));
+        assert!(is_black(&surface, 30, 15));
+        assert!(!is_black(&surface, 10, 10));
+        assert!(!is_black(&surface, 31, 15));
+    }
+
+    #[test]
+    fn test_sparkline_marks_min_and_max() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        let sparkline = Sparkline::new(vec![0.0, 10.0]);
+        sparkline.draw(&mut surface, 0, 0, Bounds::new(25, 25));
+
+        // The line is inset so the markers fit. The maximum's marker is filled,
+        // the minimum's is hollow.
+        assert!(is_black(&surface, 22, 2) && is_black(&surface, 21, 1));
+        assert!(!is_black(&surface, 2, 22) && is_black(&surface, 0, 22));
+    }
+
+    #[test]
+    fn test_chart_threshold_uses_the_accent_color() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        surface.palette = Palette::BlackWhiteRed;
+        let mut sparkline = Sparkline::new(vec![0.0, 0.0]);
+        sparkline.threshold = Some(10.0);
+        sparkline.show_min_max = false;
+        sparkline.draw(&mut surface, 0, 0, Bounds::new(20, 11));
+
+        let red = surface.rgb(Color::Accent);
+        let dashes = (0..20).filter(|&x| *surface.img.get_pixel(x, 0) == red).count();
+        assert!(dashes > 10 && dashes < 20, "{}", dashes);
+    }
+
+    #[test]
+    fn test_bar_chart_grows_bars_from_zero() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        let mut chart = BarChart::new(vec![1.0, 2.0]);
+        chart.value_labels = false;
+        chart.draw(&mut surface, 0, 0, Bounds::new(21, 11));
+
+        // The axes take up the left column and the bottom row, which leaves
+        // a 20x10 plot for two 9 pixel wide bars with 2 pixels between them.
+        assert!((0..11).all(|y| is_black(&surface, 0, y)));
+        assert!((0..21).all(|x| is_black(&surface, x, 10)));
+        assert!(!is_black(&surface, 5, 2) && is_black(&surface, 5, 6));
+        assert!(is_black(&surface, 16, 0) && is_black(&surface, 16, 9));
+        assert!(!is_black(&surface, 10, 5) && !is_black(&surface, 11, 5));
+    }
+
+    #[test]
+    fn test_line_chart_labels_its_axes() {
+        let mut unlabeled = Surface::new(200, 100).unwrap();
+        let mut chart = LineChart::new(vec![10.0, 30.0, 20.0]);
+        chart.value_labels = false;
+        chart.draw(&mut unlabeled, 0, 0, Bounds::new(200, 100));
+
+        let mut labeled = Surface::new(200, 100).unwrap();
+        chart.value_labels = true;
+        chart.labels = vec![String::from("6:00"), String::from("18:00")];
+        chart.draw(&mut labeled, 0, 0, Bounds::new(200, 100));
+
+        // Without labels, the value axis is on the left edge, the category
+        // axis on the bottom edge.
+        assert!(is_black(&unlabeled, 0, 50) && is_black(&unlabeled, 100, 99));
+        // The labels push both axes inwards.
+        let axis_x = (0..200).find(|&x| is_black(&labeled, x, 50)).unwrap();
+        let axis_y = (0..100).rev().find(|&y| is_black(&labeled, 100, y)).unwrap();
+        assert!(axis_x > 5, "{}", axis_x);
+        assert!(axis_y < 95, "{}", axis_y);
+        // There's text in the gutter and under the axis.
+        assert!((0..axis_x - 1).any(|x| (0..20).any(|y| is_black(&labeled, x, y))));
+        assert!((0..200).any(|x| (axis_y + 1..100).any(|y| is_black(&labeled, x, y))));
+    }
+
+    #[test]
+    fn test_category_labels_stay_inside_the_chart() {
+        let labels = vec![String::from("Mon"), String::from("Tue")];
+        let mut bar_chart = BarChart::new(vec![1.0, 2.0]);
+        bar_chart.show_min_max = false;
+        bar_chart.labels = labels.clone();
+        let mut line_chart = LineChart::new(vec![1.0, 2.0]);
+        line_chart.labels = labels;
+
+        for chart in [&bar_chart as &dyn View, &line_chart] {
+            let mut surface = Surface::new(100, 100).unwrap();
+            chart.draw(&mut surface, 0, 0, Bounds::new(100, 60));
+            let axis_y = (0..60).find(|&y| (50..100).all(|x| is_black(&surface, x, y))).unwrap();
+            let label_top = (axis_y + 1..100).find(|&y| (0..100).any(|x| is_black(&surface, x, y))).unwrap();
+            let label_bottom = (0..100).rev().find(|&y| (0..100).any(|x| is_black(&surface, x, y))).unwrap();
+            assert!(label_top >= axis_y + 1 + CHART_LABEL_SPACING, "{} {}", axis_y, label_top);
+            assert!(label_bottom < 60, "{}", label_bottom);
+        }
+    }
+
+    #[test]
+    fn test_progress_bar_is_flexible_along_its_direction() {
+        let surface = Surface::new(100, 100).unwrap();
+        let horizontal = ProgressBar::horizontal(0.5);
+        assert_eq!(Bounds::new(80, 10), horizontal.bounds(&surface, Bounds::new(80, 30)));
+        assert_eq!(Bounds::new(0, 10), horizontal.bounds(&surface, Bounds::new(0, 0).zero_hinted()));
+        let vertical = ProgressBar::vertical(0.5);
+        assert_eq!(Bounds::new(10, 30), vertical.bounds(&surface, Bounds::new(80, 30)));
+    }
+
+    #[test]
+    fn test_progress_bar_fills_up_to_its_value() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        ProgressBar::horizontal(0.75).draw(&mut surface, 0, 0, Bounds::new(42, 30));
+        // 40 pixels inside the outline, 30 of them filled.
+        assert!((0..42).all(|x| is_black(&surface, x, 0)));
+        assert!(is_black(&surface, 30, 5) && !is_black(&surface, 31, 5));
+        assert!(is_black(&surface, 41, 5));
+
+        let mut surface = Surface::new(100, 100).unwrap();
+        ProgressBar::vertical(0.25).draw(&mut surface, 0, 0, Bounds::new(30, 42));
+        assert!(is_black(&surface, 5, 31) && !is_black(&surface, 5, 30));
+    }
+
+    #[test]
+    fn test_progress_bar_clamps_its_value() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        ProgressBar::horizontal(1.5).draw(&mut surface, 0, 0, Bounds::new(42, 30));
+        assert!((1..41).all(|x| is_black(&surface, x, 5)));
+        assert!(!is_black(&surface, 42, 5));
+
+        let mut surface = Surface::new(100, 100).unwrap();
+        ProgressBar::horizontal(f32::NAN).draw(&mut surface, 0, 0, Bounds::new(42, 30));
+        assert!((1..41).all(|x| !is_black(&surface, x, 5)));
+    }
+
+    #[test]
+    fn test_gauge_stays_a_semicircle() {
+        let surface = Surface::new(100, 100).unwrap();
+        let gauge = Gauge::new(0.5);
+        assert_eq!(Bounds::new(60, 30), gauge.bounds(&surface, Bounds::new(80, 30)));
+        assert_eq!(Bounds::new(40, 20), gauge.bounds(&surface, Bounds::new(40, 30)));
+        assert_eq!(Bounds::new(0, 0), gauge.bounds(&surface, Bounds::new(0, 0).zero_hinted()));
+    }
+
+    #[test]
+    fn test_gauge_fills_from_the_left() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        Gauge::new(0.4).draw(&mut surface, 0, 0, Bounds::new(60, 30));
+        // Inside the arc, close to the left end, at the top, and close to the
+        // right end.
+        assert!(is_black(&surface, 5, 25));
+        assert!(!is_black(&surface, 30, 5));
+        assert!(!is_black(&surface, 54, 25));
+        // The center stays empty.
+        assert!(!is_black(&surface, 30, 25));
+    }
+
+    #[test]
+    fn test_level_indicator_rounds_to_segments() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        // 5 segments of 8 pixels, with 2 pixels after each.
+        LevelIndicator::horizontal(0.72).draw(&mut surface, 0, 0, Bounds::new(50, 30));
+        let filled = (0..5).filter(|i| is_black(&surface, i * 10 + 4, 5)).count();
+        assert_eq!(4, filled);
+        // Empty segments are outlined.
+        assert!(is_black(&surface, 40, 5) && is_black(&surface, 47, 5));
+    }
+
+    #[test]
+    fn test_level_indicator_battery_cap() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        let mut battery = LevelIndicator::vertical(0.0);
+        battery.battery_cap = true;
+        battery.segments = 4;
+        battery.draw(&mut surface, 0, 0, Bounds::new(30, 50));
+        // The cap is 2 pixels long, on top of the segments, and half as wide.
+        assert!(is_black(&surface, 5, 0) && !is_black(&surface, 1, 0));
+        // The lowest segment sits on the bottom edge.
+        assert!(is_black(&surface, 5, 49));
+    }
+
+    /// A view that claims to be `size`, but fills an `ink` sized area.
+    struct Overdraw {
+        size: Bounds,
+        ink: Bounds,
+    }
+
+    impl View for Overdraw {
+        fn bounds(&self, _surface: &Surface, _suggested_bounds: Bounds) -> Bounds {
+            self.size
+        }
+
+        fn draw(&self, surface: &mut Surface, x: u32, y: u32, _suggested_bounds: Bounds) {
+            for py in y..y + self.ink.height {
+                for px in x..x + self.ink.width {
+                    surface.set_pixel(px, py, image::Rgb([0, 0, 0]));
+                }
+            }
+        }
+
+        fn padding_data(&self) -> Padding {
+            Padding::zero()
+        }
+
+        fn set_padding_data(&mut self, _: Padding) {
+        }
+    }
+
+    fn overdraw(width: u32, height: u32) -> Box<Overdraw> {
+        Box::new(Overdraw { size: Bounds::new(width, height), ink: Bounds::new(80, 80) })
     }
 
     #[test]
-    fn test_can_mix_hstack_and_vstack() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut top_hstack = HStack::new();
-        top_hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 50, 30)));
-        top_hstack.views.push(Box::new(Spacer::horizontal()));
-        top_hstack.views.push(Box::new(TestView::monitored(2, register.clone(), 70, 35)));
-        let mut bottom_hstack = HStack::new();
-        bottom_hstack.views.push(Box::new(TestView::monitored(3, register.clone(), 50, 40)));
-        bottom_hstack.views.push(Box::new(TestView::monitored(4, register.clone(), 70, 40)));
+    fn test_rect_intersection() {
+        let a = Rect::new(10, 10, 20, 20);
+        assert_eq!(Rect::new(20, 15, 10, 5), a.intersection(Rect::new(20, 15, 50, 5)));
+        assert_eq!(0, a.intersection(Rect::new(40, 40, 5, 5)).width);
+        assert!(a.contains(10, 29) && !a.contains(30, 10) && !a.contains(9, 10));
+    }
+
+    #[test]
+    fn test_pixels_outside_of_the_clip_are_discarded() {
+        let mut surface = Surface::new(50, 50).unwrap();
+        let black = image::Rgb([0, 0, 0]);
+        surface.push_clip(Rect::new(10, 10, 20, 20));
+        surface.push_clip(Rect::new(0, 0, 15, 15));
+        assert_eq!(Rect::new(10, 10, 5, 5), surface.clip_rect());
+        surface.set_pixel(12, 12, black);
+        surface.set_pixel(16, 16, black);
+        surface.pop_clip();
+        surface.set_pixel(17, 17, black);
+        surface.blend_pixel(5, 5, black, 255);
+        surface.pop_clip();
+        surface.set_pixel(6, 6, black);
+        // Outside of the surface.
+        surface.set_pixel(60, 6, black);
+
+        assert!(is_black(&surface, 12, 12));
+        assert!(!is_black(&surface, 16, 16));
+        assert!(is_black(&surface, 17, 17));
+        assert!(!is_black(&surface, 5, 5));
+        assert!(is_black(&surface, 6, 6));
+    }
+
+    #[test]
+    fn test_vstack_clips_children_to_their_slot() {
+        let mut surface = Surface::new(100, 100).unwrap();
         let mut vstack = VStack::new();
-        vstack.views.push(Box::new(top_hstack));
-        vstack.views.push(Box::new(Spacer::vertical()));
-        vstack.views.push(Box::new(TestView::monitored(5, register.clone(), 150, 100)));
-        vstack.views.push(Box::new(Spacer::vertical()));
-        vstack.views.push(Box::new(bottom_hstack));
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        vstack.draw(&mut surface, 0, 0, bounds.infinitely_hinted());
-        // The first view should be in the top left corner.
-        assert!(register.was_drawn_at(1, 0, 0));
-        // The second view in the top right corner.
-        assert!(register.was_drawn_at(2, 400 - 70, 0));
-        // The third view is in the bottom left corner. The fourth one follows
-        // immediately.
-        assert!(register.was_drawn_at(3, 0, 400 - 40));
-        assert!(register.was_drawn_at(4, 50, 400 - 40));
-        // Number five is somewhere in the middle. We have the top hstack taking
-        // up 35 pixels. And the bottom hstack taking up 40 pixels. So that
-        // leaves 400 - (35 + 40) = 325 pixels. View number five itself takes up
-        // 100 pixels, so we have 225 pixels to distribute among the two
-        // vertical spacers. We size them equally, so 112 pixels for the first
-        // and 113 for the second.
-        assert!(register.was_drawn_at(5, 0, 35 + 112));
+        vstack.views.push(overdraw(10, 10));
+        vstack.views.push(Box::new(TestView::new(10, 10)));
+        vstack.draw(&mut surface, 0, 0, Bounds::new(50, 100));
+
+        assert!(is_black(&surface, 5, 5) && is_black(&surface, 49, 9));
+        assert!(!is_black(&surface, 5, 10));
+        assert!(!is_black(&surface, 50, 5));
     }
 
     #[test]
-    fn test_can_set_padding_on_view() {
-        let mut view = TestView::new(300, 300);
-        assert_eq!(0, view.padding.left);
-        assert_eq!(0, view.padding.right);
-        assert_eq!(0, view.padding.top);
-        assert_eq!(0, view.padding.bottom);
-        view.padding(Edge::Left, 1);
-        view.padding(Edge::Right, 2);
-        view.padding(Edge::Top, 3);
-        view.padding(Edge::Bottom, 4);
-        assert_eq!(1, view.padding.left);
-        assert_eq!(2, view.padding.right);
-        assert_eq!(3, view.padding.top);
-        assert_eq!(4, view.padding.bottom);
+    fn test_hstack_clips_children_to_their_slot() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(TestView::new(10, 10)));
+        hstack.views.push(overdraw(10, 10));
+        hstack.draw(&mut surface, 0, 0, Bounds::new(100, 50));
+
+        assert!(is_black(&surface, 15, 5) && is_black(&surface, 19, 49));
+        assert!(!is_black(&surface, 20, 5));
+        assert!(!is_black(&surface, 15, 50));
     }
 
     #[test]
-    fn test_vstack_renders_top_and_left_padding() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
+    fn test_zstack_and_grid_clip_children() {
+        let mut surface = Surface::new(100, 100).unwrap();
+        let mut zstack = ZStack::new();
+        zstack.push(overdraw(20, 20), Alignment::TopLeft);
+        zstack.draw(&mut surface, 0, 0, Bounds::new(100, 100));
+        assert!(is_black(&surface, 19, 19) && !is_black(&surface, 20, 5));
+
+        let mut surface = Surface::new(100, 100).unwrap();
+        let mut grid = Grid::new(vec![Track::Fixed(10), Track::Fixed(10)], vec![Track::Fixed(10)]);
+        grid.cells.push(GridCell::new(overdraw(10, 10), 0, 0));
+        grid.draw(&mut surface, 0, 0, Bounds::new(100, 100));
+        assert!(is_black(&surface, 9, 9) && !is_black(&surface, 10, 5) && !is_black(&surface, 5, 10));
+    }
+
+    #[test]
+    fn test_oversized_text_does_not_panic() {
+        let mut surface = Surface::new(20, 20).unwrap();
+        let text = Text::new(String::from("Much too long for this surface"), 30.0, DEFAULT_FONT);
+        let bounds = text.bounds(&surface, Bounds::new(500, 500));
+        text.draw(&mut surface, 10, 10, bounds);
+    }
+
+    #[test]
+    fn test_layout_reports_the_overflowing_stack() {
+        let surface = Surface::new(100, 100).unwrap();
         let mut vstack = VStack::new();
-        vstack.views.push(Box::new(TestView::monitored(1, register.clone(), 150, 100)));
-        vstack.padding(Edge::Left, 10);
-        vstack.padding(Edge::Right, 5);
-        vstack.padding(Edge::Top, 15);
-        vstack.padding(Edge::Bottom, 2);
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        assert_eq!(150 + 15, vstack.bounds(&surface, bounds.zero_hinted()).width);
-        assert_eq!(150 + 15, vstack.bounds(&surface, bounds.optimally_hinted()).width);
-        assert_eq!(150 + 15, vstack.bounds(&surface, bounds.infinitely_hinted()).width);
-        assert_eq!(100 + 17, vstack.bounds(&surface, bounds.zero_hinted()).height);
-        assert_eq!(100 + 17, vstack.bounds(&surface, bounds.optimally_hinted()).height);
-        assert_eq!(100 + 17, vstack.bounds(&surface, bounds.infinitely_hinted()).height);
-        vstack.draw(&mut surface, 0, 0, bounds.infinitely_hinted());
-        assert!(register.was_drawn_at(1, 10, 15));
+        vstack.views.push(Box::new(TestView::new(10, 30)));
+        vstack.views.push(Box::new(TestView::new(10, 30)));
+        let overflow = vstack.layout(&surface, Bounds::new(50, 50)).unwrap_err();
+
+        assert_eq!(vec!["VStack"], overflow.path);
+        assert_eq!((0, 10), (overflow.width_excess, overflow.height_excess));
+        assert_eq!("VStack is 10 pixels too tall", overflow.to_string());
+
+        assert!(vstack.layout(&surface, Bounds::new(50, 60)).is_ok());
     }
 
     #[test]
-    fn test_vstack_padding_cuts_into_space_for_views() {
-        // When the VStack includes a spacer like this:
-        //
-        // +-------------------+
-        // |    Test View      |
-        // |   (100 x 150)     |
-        // +-------------------+
-        // |      Spacer       |
-        // +-------------------+
-        // |    Test View      |
-        // |    (75 x 75)      |
-        // +-------------------+
-        //
-        // Since this is a VStack, let's think about the height of the thing
-        // first. Let's say that we have 200 px of height for the entire thing.
-        // Then in all three hinting modes, the VStack will take up 225 px.
-        // Since the test views are not willing to flex and they need a minimum
-        // of 225 px, no matter the suggested bounds for the view.
-        //
-        // Padding is also not flexible. So, in this situation, the height of
-        // the VStack would increase further by the top and bottom padding, no
-        // matter the suggested bounds.
-        //
-        // If we expand the suggested bounds to _just_ cover the required height
-        // of the views plus the padding -- let's say that we pad by 10 px at
-        // the top and by 5 px at the bottom, so 240 px in total -- then the
-        // spacer would always collapse to zero. Everything beyond 240 px would
-        // be left over for the spacer in infinitely- and optimally-hinted modes.
-        //
-        // As for the horizontal extend of the VStack: that part is reasonably
-        // easy: the VStack just adds its own padding to the total width
-        // required.
-        //
-        // To test this, we'll embed a VStack in another VStack, like this:
-        //
-        // +--------------------------+
-        // |        Test View         |
-        // +--------------------------+
-        // |    VStack w/ padding     |
-        // | +----------------------+ |
-        // | |      Test View       | |
-        // | +----------------------+ |
-        // | |        Spacer        | |
-        // | +----------------------+ |
-        // | |      Test View       | |
-        // | +----------------------+ |
-        // +--------------------------+
-        // |        Test View         |
-        // +--------------------------+
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut inner_stack = VStack::new();
-        inner_stack.views.push(Box::new(TestView::monitored(2, register.clone(), 100, 150)));
-        inner_stack.views.push(Box::new(Spacer::vertical()));
-        inner_stack.views.push(Box::new(TestView::monitored(3, register.clone(), 75, 75)));
-        inner_stack.padding(Edge::Top, 10);
-        inner_stack.padding(Edge::Bottom, 5);
-        inner_stack.padding(Edge::Left, 15);
-        inner_stack.padding(Edge::Right, 2);
-        let mut outer_stack = VStack::new();
-        outer_stack.views.push(Box::new(TestView::monitored(1, register.clone(), 100, 40)));
-        outer_stack.views.push(Box::new(inner_stack));
-        outer_stack.views.push(Box::new(TestView::monitored(4, register.clone(), 80, 50)));
-        // Give the entire outer stack 350 px of height. That should leave
-        // 350 - 150 - 75 - 40 - 50 - 10 - 5 = 20 px for the spacer.
-        let bounds = surface.bounds() - Bounds::new(150, 150);
-        outer_stack.draw(&mut surface, 0, 0, bounds.optimally_hinted());
-        assert!(register.was_drawn_at(1, 0, 0));
-        // 15 (left padding) x 40 + 10 (top padding)
-        assert!(register.was_drawn_at(2, 15, 50));
-        // Spacer in between 2 and 3.
-        // 15 (left padding) x 40 + 20 (spacer) + 150 + 10 (top padding)
-        assert!(register.was_drawn_at(3, 15, 220));
-        // 0 x 40 + 20 (spacer) + 150 + 75 + 10 (top padding) + 5 (bottom padding)
-        assert!(register.was_drawn_at(4, 0, 300));
+    fn test_layout_points_at_the_innermost_view() {
+        let surface = Surface::new(100, 100).unwrap();
+        let mut grid = Grid::new(vec![Track::Fixed(30)], vec![Track::Fixed(30)]);
+        let text = Text::new(String::from("Much too long"), 20.0, DEFAULT_FONT);
+        grid.cells.push(GridCell::new(Box::new(text), 0, 0));
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(grid));
+        let overflow = hstack.layout(&surface, Bounds::new(100, 50)).unwrap_err();
+
+        assert_eq!(vec!["HStack", "Grid", "Text"], overflow.path);
+        assert_eq!("Text", overflow.view());
+        assert!(overflow.width_excess > 0);
+        assert!(overflow.to_string().ends_with("too wide (in HStack > Grid)"));
     }
 
     #[test]
-    fn test_hstack_renders_top_and_left_padding() {
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
+    fn test_layout_reports_overflows_along_both_axes() {
+        let surface = Surface::new(100, 100).unwrap();
+        let overflow = TestView::new(40, 35).layout(&surface, Bounds::new(30, 30)).unwrap_err();
+        assert_eq!((10, 5), (overflow.width_excess, overflow.height_excess));
+        assert_eq!("TestView is 10 pixels too wide and 5 pixels too tall", overflow.to_string());
+
+        let rotate = Rotate::new(Box::new(TestView::new(40, 35)), Rotation::Clockwise90);
+        let overflow = rotate.layout(&surface, Bounds::new(30, 30)).unwrap_err();
+        assert_eq!((5, 10), (overflow.width_excess, overflow.height_excess));
+    }
+
+    #[test]
+    fn test_layout_can_be_called_on_concrete_views() {
+        let surface = Surface::new(100, 100).unwrap();
+        let text = Text::new(String::from("Much too long"), 20.0, DEFAULT_FONT);
+        let overflow = text.layout(&surface, Bounds::new(30, 30)).unwrap_err();
+        assert_eq!(vec!["Text"], overflow.path);
+
+        let rich = RichText::new(vec![Span::new("Much too long")], 20.0, DEFAULT_FONT);
+        assert!(rich.layout(&surface, Bounds::new(30, 30)).is_err());
+        let barcode = Barcode::new(Symbology::Code128, "ABC").unwrap();
+        assert!(barcode.layout(&surface, Bounds::new(100, 100)).is_ok());
+    }
+
+    #[test]
+    fn test_layout_of_rotated_views_swaps_the_axis() {
+        let surface = Surface::new(100, 100).unwrap();
+        let rotate = Rotate::new(Box::new(TestView::new(40, 10)), Rotation::Clockwise90);
+        let overflow = rotate.layout(&surface, Bounds::new(50, 30)).unwrap_err();
+
+        assert_eq!(vec!["Rotate", "TestView"], overflow.path);
+        assert_eq!((0, 10), (overflow.width_excess, overflow.height_excess));
+    }
+
+    #[test]
+    fn test_stacks_with_too_much_spacing_do_not_panic() {
+        let mut surface = Surface::new(20, 20).unwrap();
+        let mut vstack = VStack::new();
+        vstack.spacing = 50;
+        vstack.views.push(Box::new(TestView::new(10, 10)));
+        vstack.views.push(Box::new(TestView::new(10, 10)));
+        vstack.draw(&mut surface, 0, 0, Bounds::new(20, 20));
+        assert!(vstack.layout(&surface, Bounds::new(20, 20)).is_err());
+
         let mut hstack = HStack::new();
-        hstack.views.push(Box::new(TestView::monitored(1, register.clone(), 150, 100)));
-        hstack.padding(Edge::Left, 10);
-        hstack.padding(Edge::Right, 5);
-        hstack.padding(Edge::Top, 15);
-        hstack.padding(Edge::Bottom, 2);
-        let bounds = surface.bounds() - Bounds::new(100, 100);
-        assert_eq!(150 + 15, hstack.bounds(&surface, bounds.zero_hinted()).width);
-        assert_eq!(150 + 15, hstack.bounds(&surface, bounds.optimally_hinted()).width);
-        assert_eq!(150 + 15, hstack.bounds(&surface, bounds.infinitely_hinted()).width);
-        assert_eq!(100 + 17, hstack.bounds(&surface, bounds.zero_hinted()).height);
-        assert_eq!(100 + 17, hstack.bounds(&surface, bounds.optimally_hinted()).height);
-        assert_eq!(100 + 17, hstack.bounds(&surface, bounds.infinitely_hinted()).height);
-        hstack.draw(&mut surface, 0, 0, bounds.infinitely_hinted());
-        assert!(register.was_drawn_at(1, 10, 15));
+        hstack.spacing = 50;
+        hstack.views.push(Box::new(TestView::new(10, 10)));
+        hstack.views.push(Box::new(TestView::new(10, 10)));
+        hstack.draw(&mut surface, 0, 0, Bounds::new(20, 20));
+        assert!(hstack.layout(&surface, Bounds::new(20, 20)).is_err());
+    }
+
+    fn debug_stack() -> VStack {
+        let mut text = TestView::new(20, 10);
+        text.padding = Padding { left: 2, right: 2, top: 2, bottom: 2 };
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(text));
+        vstack.views.push(Box::new(Spacer::vertical()));
+        vstack.views.push(Box::new(TestView::new(20, 10)));
+        vstack
     }
 
     #[test]
-    fn test_hstack_padding_cuts_into_space_for_views() {
-        // The argument for the padding goes pretty much exactly like in the
-        // VStack test. We use the following test setup:
-        //
-        // +--------+---------------------------------+--------+
-        // |        |       HStack w/ padding         |        |
-        // |  Test  | +--------+----------+--------+  |  Test  |
-        // |  View  | |  Test  |  Spacer  |  Test  |  |  View  |
-        // |        | |  View  |          |  View  |  |        |
-        // |        | +--------+----------+--------+  |        |
-        // +--------+---------------------------------+--------+
-        let mut surface = Surface::new(500, 500).unwrap();
-        let mut register = Rc::new(DrawingRegister::new());
-        let mut inner_stack = HStack::new();
-        inner_stack.views.push(Box::new(TestView::monitored(2, register.clone(), 150, 100)));
-        inner_stack.views.push(Box::new(Spacer::horizontal()));
-        inner_stack.views.push(Box::new(TestView::monitored(3, register.clone(), 75, 75)));
-        inner_stack.padding(Edge::Left, 10);
-        inner_stack.padding(Edge::Right, 5);
-        inner_stack.padding(Edge::Top, 15);
-        inner_stack.padding(Edge::Bottom, 2);
-        let mut outer_stack = HStack::new();
-        outer_stack.views.push(Box::new(TestView::monitored(1, register.clone(), 40, 100)));
-        outer_stack.views.push(Box::new(inner_stack));
-        outer_stack.views.push(Box::new(TestView::monitored(4, register.clone(), 50, 80)));
-        // Give the entire outer stack 350 px of width. That should leave
-        // 350 - 150 - 75 - 40 - 50 - 10 - 5 = 20 px for the spacer.
-        let bounds = surface.bounds() - Bounds::new(150, 150);
-        outer_stack.draw(&mut surface, 0, 0, bounds.optimally_hinted());
-        assert!(register.was_drawn_at(1, 0, 0));
-        // 40 + 10 (left padding) x 15 (top padding)
-        assert!(register.was_drawn_at(2, 50, 15));
-        // Spacer in between 2 and 3.
-        // 40 + 20 (spacer) + 150 + 10 (left padding) x 15 (top padding)
-        assert!(register.was_drawn_at(3, 220, 15));
-        // 40 + 20 (spacer) + 150 + 75 + 10 (left padding) + 5 (right padding) x 0
-        assert!(register.was_drawn_at(4, 300, 0));
+    fn test_draw_view_records_the_layout_tree() {
+        let mut surface = Surface::new(50, 50).unwrap();
+        surface.draw_view(&debug_stack(), 0, 0, Bounds::new(50, 50));
+        assert!(surface.layout_tree().is_empty());
+
+        surface.debug_layout = true;
+        surface.draw_view(&debug_stack(), 0, 0, Bounds::new(50, 50));
+        let tree = surface.layout_tree();
+        assert_eq!(1, tree.len());
+        assert_eq!("VStack", tree[0].view);
+        let children: Vec<_> = tree[0].children.iter()
+            .map(|c| (c.view.as_str(), c.frame(), c.slot))
+            .collect();
+        assert_eq!(vec![
+            ("TestView", Rect::new(0, 0, 20, 10), Rect::new(0, 0, 50, 10)),
+            ("Spacer", Rect::new(0, 10, 0, 30), Rect::new(0, 10, 50, 30)),
+            ("TestView", Rect::new(0, 40, 20, 10), Rect::new(0, 40, 50, 10)),
+        ], children);
+    }
+
+    #[test]
+    fn test_layout_preview_outlines_frames_padding_and_spacers() {
+        let mut surface = Surface::new(50, 50).unwrap();
+        surface.debug_layout = true;
+        let mut hstack = HStack::new();
+        hstack.views.push(Box::new(debug_stack()));
+        hstack.views.push(Box::new(Spacer::horizontal()));
+        surface.draw_view(&hstack, 0, 0, Bounds::new(50, 50));
+        let preview = surface.layout_preview();
+
+        assert_eq!(DEBUG_FRAME_COLOR, *preview.get_pixel(0, 49));
+        assert_eq!(DEBUG_FRAME_COLOR, *preview.get_pixel(19, 45));
+        assert_eq!(DEBUG_PADDING_COLOR, *preview.get_pixel(2, 5));
+        // Spacers are outlined along the space they take up, which covers the
+        // edges of the stacks they're in.
+        assert_eq!(DEBUG_SPACER_COLOR, *preview.get_pixel(19, 30));
+        assert_eq!(DEBUG_SPACER_COLOR, *preview.get_pixel(30, 0));
+        assert_eq!(DEBUG_SPACER_COLOR, *preview.get_pixel(49, 49));
+        assert_eq!(WHITE, *preview.get_pixel(30, 30));
+        // The surface itself is left alone.
+        assert_eq!(WHITE, *surface.img.get_pixel(19, 45));
+    }
+
+    #[test]
+    fn test_layout_preview_outlines_flexible_shapes_like_spacers() {
+        let mut surface = Surface::new(50, 50).unwrap();
+        surface.debug_layout = true;
+        let mut bar = Rectangle::flexible();
+        bar.width = Some(20);
+        let mut vstack = VStack::new();
+        vstack.views.push(Box::new(Rectangle::new(20, 10)));
+        vstack.views.push(Box::new(Divider::horizontal()));
+        vstack.views.push(Box::new(bar));
+        surface.draw_view(&vstack, 0, 0, Bounds::new(50, 50));
+
+        let spacers: Vec<bool> = surface.layout_tree()[0].children.iter().map(|c| c.spacer).collect();
+        assert_eq!(vec![false, true, true], spacers);
+        let preview = surface.layout_preview();
+        assert_eq!(DEBUG_SPACER_COLOR, *preview.get_pixel(49, 30));
+        assert_eq!(WHITE, *preview.get_pixel(30, 9));
+    }
+
+    #[test]
+    fn test_layout_tree_includes_labels_drawn_by_views() {
+        let mut surface = Surface::new(200, 100).unwrap();
+        surface.debug_layout = true;
+        let barcode = Barcode::new(Symbology::Code128, "ABC").unwrap();
+        surface.draw_view(&barcode, 0, 0, Bounds::new(200, 100));
+        let mut chart = LineChart::new(vec![1.0, 2.0]);
+        chart.labels = vec![String::from("Mon"), String::from("Tue")];
+        surface.draw_view(&chart, 0, 0, Bounds::new(200, 100));
+
+        let children: Vec<Vec<&str>> = surface.layout_tree().iter()
+            .map(|node| node.children.iter().map(|c| c.view.as_str()).collect())
+            .collect();
+        assert_eq!(vec![vec!["Text"], vec!["Text"; 4]], children);
+    }
+
+    #[test]
+    fn test_layout_tree_serializes_to_json() {
+        let mut surface = Surface::new(50, 50).unwrap();
+        surface.debug_layout = true;
+        surface.draw_view(&TestView::new(20, 10), 5, 6, Bounds::new(20, 10));
+        let json = serde_json::to_value(surface.layout_tree()).unwrap();
+
+        assert_eq!(serde_json::json!([{
+            "type": "TestView",
+            "x": 5,
+            "y": 6,
+            "width": 20,
+            "height": 10,
+            "hint": "Optimal",
+            "padding": {"left": 0, "right": 0, "top": 0, "bottom": 0},
+            "slot": {"x": 0, "y": 0, "width": 50, "height": 50},
+            "spacer": false,
+            "children": [],
+        }]), json);
     }
 }
@@ -59,8 +59,56 @@
 //! mac = "000002287eef3cde"
 //! width = 152
 //! height = 152
+//! palette = "black-white-red"
 //! ```
 //!
+//! The `palette` setting is optional and describes the colors the panel can
+//! display: `black-white` (the default), `black-white-red`,
+//! `black-white-yellow`, or `gray4`. Before the image is sent to the tag, every
+//! pixel is mapped to a color of the tag's palette.
+//!
+//! The optional `dithering` setting determines how that mapping happens:
+//! `threshold` (the default) snaps every pixel to the closest palette color,
+//! which keeps text crisp. `floyd-steinberg`, `atkinson`, and `ordered` dither
+//! the image, which works much better for photos and logos with gradients. You
+//! can override the setting for a single run with `--dithering`.
+//!
+//! Tags that are mounted upside down or in portrait orientation get a
+//! `rotation` of 90, 180, or 270: the number of degrees the tag is turned
+//! clockwise from its normal landscape position. Tags behind a mirror or
+//! viewed from behind get `mirror = true`. `width` and `height` always
+//! describe the panel itself. Modules draw in the orientation the tag is
+//! mounted in (for 90 and 270, width and height are swapped), and the finished
+//! image is mirrored horizontally and then turned back by the same number of
+//! degrees (counter-clockwise) before it's sent to the tag, so it's upright
+//! once the tag is mounted.
+//!
+//! ```toml
+//! [[tags]]
+//! mac = "000002186fd53b14"
+//! width = 296
+//! height = 128
+//! rotation = 90
+//! ```
+//!
+//! ### Fonts
+//!
+//! Roboto, RobotoMono, and PlayfairDisplay are always available. To use other
+//! fonts, either point `fonts_dir` at a directory of TTF/OTF files, which are
+//! then available under their file name (without the extension), or register
+//! them by name in the `fonts` table. Fonts are only read once they're used.
+//!
+//! ```toml
+//! fonts_dir = "fonts"
+//!
+//! [fonts]
+//! Headline = "/usr/share/fonts/truetype/dejavu/DejaVuSerif.ttf"
+//! ```
+//!
+//! Characters that a font doesn't have (Cyrillic names, CJK, symbols) are
+//! drawn with the first font in `fallback_fonts` that has them, e.g.
+//! `fallback_fonts = ["NotoSansCJK-Regular", "NotoEmoji-Regular"]`.
+//!
 //! ## Writing to a JPEG
 //!
 //! To write to a JPEG instead of sending the image to Open ePaper Link, use the
@@ -70,6 +118,20 @@
 //! open-epaper-gen --module news-headlines --jpeg out.jpeg --width 296 --height 128
 //! ```
 //!
+//! Use `--palette` and `--dithering` to preview how the image will look on a
+//! panel, e.g. `--palette black-white-red --dithering atkinson`. If the file
+//! name ends in `.png`, the image is saved as a (lossless) PNG instead.
+//! `--rotation` and `--mirror` work like the tag settings of the same name, so
+//! you can check the image that will be sent to a rotated tag.
+//!
+//! ## Debugging Layouts
+//!
+//! If a module's layout comes out wrong, add `--debug-layout layout.png`. That
+//! saves a copy of the image (before it's quantized) with the frame of every
+//! view outlined in red, the area inside its padding in blue, and spacers in
+//! green. The layout tree -- each view's type, origin, size, and sizing hint --
+//! is written to `layout.json`.
+//!
 //! ## Building open-epaper-gen
 //!
 //! The best way to build this is to use [xtask](https://github.com/matklad/cargo-xtask).
@@ -117,6 +179,9 @@
 //! drawing the image. Right now, that includes a text view, an image view, and
 //! a very basic layout system inspired by SwiftUI.
 //!
+//! [`dither`] maps the finished image to the colors the panel can display,
+//! optionally dithering it.
+//!
 //! [`news_headlines`] contains the code for the news headlines module.
 //!
 //! External resources should go into the `resources` folder. Put global
@@ -131,20 +196,33 @@
 //! the `module` CLI parameter.
 
 mod modules;
+// The draw module is a toolkit for writing modules. Not every primitive in
+// there is used by the modules that currently ship with the app.
+#[allow(dead_code)]
 mod draw;
 mod news_headlines;
+mod dither;
+// Only used by the draw module's Barcode view.
+#[allow(dead_code)]
+mod barcode;
 
 use serde::{Deserialize};
 use reqwest::blocking::multipart;
 use anyhow::{Result, Context, bail};
 use clap::{Parser};
 use config::{Config};
-use log::{info, error};
+use log::info;
 use env_logger::Env;
-use image::ImageFormat;
+use image::{ImageFormat, RgbImage};
+use image::codecs::jpeg::JpegEncoder;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
 use tempfile::NamedTempFile;
 
-use crate::draw::Surface;
+use crate::draw::{Surface, Palette, Rotation};
+use crate::dither::Dithering;
 use crate::news_headlines::NewsHeadlines;
 use crate::modules::{InfoView, Module};
 
@@ -166,8 +244,8 @@ struct Cli {
     #[arg(long)]
     height: Option<u32>,
 
-    /// Path to an output JPEG. You'll also need to specify the output width and
-    /// height.
+    /// Path to an output JPEG (or PNG, if the path ends in .png). You'll also
+    /// need to specify the output width and height.
     #[arg(long, required = true, group = "output", requires = "width", requires = "height")]
     jpeg: Option<String>,
 
@@ -175,10 +253,37 @@ struct Cli {
     #[arg(long, required = true, group = "output")]
     tag: Option<String>,
 
+    /// The color palette of the panel (only relevant when using JPEG output,
+    /// tags take their palette from the config file). Defaults to black-white.
+    #[arg(long, value_enum)]
+    palette: Option<Palette>,
+
+    /// How to dither the image to the panel's palette. Overrides the setting
+    /// from the config file. Defaults to threshold.
+    #[arg(long, value_enum)]
+    dithering: Option<Dithering>,
+
+    /// How far the panel is mounted rotated clockwise, in degrees: 0, 90, 180,
+    /// or 270 (only relevant when using JPEG output, tags take their rotation
+    /// from the config file). The width and height describe the panel itself.
+    #[arg(long, value_parser = parse_rotation)]
+    rotation: Option<Rotation>,
+
+    /// Mirror the image, for panels that are viewed in a mirror (only relevant
+    /// when using JPEG output).
+    #[arg(long)]
+    mirror: bool,
+
     /// The config file to use (will default to config.toml in the current
     /// directory).
     #[arg(long)]
     config: Option<String>,
+
+    /// Path to a PNG to draw a layout preview to: the image as the module
+    /// drew it, with the frame of every view outlined. The measured layout
+    /// tree is written next to it, with a .json extension.
+    #[arg(long)]
+    debug_layout: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -186,16 +291,63 @@ struct Tag {
     mac: String,
     width: u32,
     height: u32,
+    #[serde(default = "default_palette")]
+    palette: Palette,
+    dithering: Option<Dithering>,
+    #[serde(default)]
+    rotation: Rotation,
+    #[serde(default)]
+    mirror: bool,
+}
+
+fn default_palette() -> Palette {
+    Palette::BlackWhite
+}
+
+fn parse_rotation(degrees: &str) -> Result<Rotation, String> {
+    let degrees: u32 = degrees.parse()
+        .map_err(|_| format!("Invalid rotation {:?}, has to be 0, 90, 180, or 270", degrees))?;
+    Rotation::try_from(degrees)
 }
 
 #[derive(Debug, Deserialize)]
 struct Settings {
     epaper_link_host: String,
     tags: Vec<Tag>,
+    /// A directory with additional fonts.
+    fonts_dir: Option<String>,
+    /// Additional fonts, by name.
+    #[serde(default)]
+    fonts: HashMap<String, String>,
+    /// Fonts to use for characters the font of a text doesn't have.
+    #[serde(default)]
+    fallback_fonts: Vec<String>,
 }
 
 fn find_tag(settings: &Settings, mac: &str) -> Option<Tag> {
-    settings.tags.iter().find(|t| t.mac == mac).map(|t| t.clone())
+    settings.tags.iter().find(|t| t.mac == mac).cloned()
+}
+
+/// Save the image to `path`, picking the format based on the file extension.
+fn save_image(img: &RgbImage, path: &Path) -> Result<()> {
+    let format = ImageFormat::from_path(path)
+        .with_context(|| format!("Can't determine image format for {:?}", path))?;
+    save_image_with_format(img, path, format)
+}
+
+/// Save the image to `path` in the given format. JPEGs are saved with maximum
+/// quality: every pixel has already been quantized to the panel's palette, and
+/// we don't want compression artifacts to undo that (or to smear a dithering
+/// pattern). Use PNG for a lossless preview.
+fn save_image_with_format(img: &RgbImage, path: &Path, format: ImageFormat) -> Result<()> {
+    if format == ImageFormat::Jpeg {
+        let file = File::create(path)
+            .with_context(|| format!("Can't create {:?}", path))?;
+        img.write_with_encoder(JpegEncoder::new_with_quality(BufWriter::new(file), 100))?;
+    } else {
+        img.save_with_format(path, format)?;
+    }
+    Ok(())
 }
 
 fn main() -> Result<()> {
@@ -236,43 +388,87 @@ fn main() -> Result<()> {
     // width and height as command line arguments. Or they have specified a tag
     // ID, in which case we can look up that tag in our config file and find the
     // width and height that way.
-    let (surface_width, surface_height) = if cli.jpeg.is_some() {
+    // The same goes for the palette and dithering, except that they're
+    // optional. The dithering from the command line always wins over the
+    // dithering from the config file.
+    let (surface_width, surface_height, palette, tag_dithering, rotation, mirror) = if cli.jpeg.is_some() {
         (
             cli.width
                 .expect("You need to specify width for JPEG output"),
             cli.height
-                .expect("You need to specify height for JPEG output")
+                .expect("You need to specify height for JPEG output"),
+            cli.palette.unwrap_or(Palette::BlackWhite),
+            None,
+            cli.rotation.unwrap_or_default(),
+            cli.mirror,
         )
     } else {
         let tag = find_tag(&settings, tag_mac).unwrap();
-        (tag.width, tag.height)
+        (tag.width, tag.height, tag.palette, tag.dithering, tag.rotation, tag.mirror)
     };
+    let dithering = cli.dithering
+        .or(tag_dithering)
+        .unwrap_or(Dithering::Threshold);
 
+    // Modules draw in the orientation the tag is mounted in, which is
+    // portrait for sideways rotations. The image is turned into the panel's
+    // orientation once it's done.
+    let (surface_width, surface_height) = if rotation.is_sideways() {
+        (surface_height, surface_width)
+    } else {
+        (surface_width, surface_height)
+    };
     let mut surface = Surface::new(surface_width, surface_height)
         .with_context(|| {
             format!("Could not create surface {:?}x{:?}", surface_width, surface_height)
         })?;
+    surface.palette = palette;
+    if let Some(ref dir) = settings.fonts_dir {
+        let count = surface.register_font_dir(dir)?;
+        info!("Registered {} fonts from {:?}", count, dir);
+    }
+    for (name, path) in &settings.fonts {
+        surface.register_font_file(name, path);
+    }
+    surface.fallback_fonts = settings.fallback_fonts.clone();
+    surface.debug_layout = cli.debug_layout.is_some();
 
     let options = module.generate(&mut surface)
         .with_context(|| format!("Module {:?} reported an error", cli.module))?;
 
-    if cli.jpeg.is_some() {
-        info!("Saving image to {:?}", cli.jpeg.clone());
-        surface.img.save(cli.jpeg.unwrap())?;
+    if let Some(ref preview) = cli.debug_layout {
+        let preview = Path::new(preview);
+        let tree = preview.with_extension("json");
+        info!("Saving layout preview to {:?} and layout tree to {:?}", preview, tree);
+        save_image_with_format(&surface.layout_preview(), preview, ImageFormat::Png)?;
+        fs::write(&tree, serde_json::to_string_pretty(surface.layout_tree())?)
+            .with_context(|| format!("Can't write layout tree to {:?}", tree))?;
+    }
+
+    if rotation != Rotation::None || mirror {
+        info!("Orienting image for the panel: rotation {:?}, mirror {:?}", rotation, mirror);
+        surface.orient(rotation, mirror);
+    }
+
+    info!("Quantizing image to palette {:?} using {:?} dithering", palette, dithering);
+    surface.quantize(dithering);
+
+    if let Some(jpeg) = cli.jpeg {
+        info!("Saving image to {:?}", jpeg);
+        save_image(&surface.img, Path::new(&jpeg))?;
         return Ok(())
     }
 
     let temp_jpeg = NamedTempFile::new()?;
     info!("Saving image to temporary file {:?}", temp_jpeg.path());
-    surface.img.save_with_format(temp_jpeg.path(), ImageFormat::Jpeg)?;
+    save_image_with_format(&surface.img, temp_jpeg.path(), ImageFormat::Jpeg)?;
 
     let client = reqwest::blocking::Client::new();
     let mut form = multipart::Form::new()
         .text("mac", tag_mac.clone())
         .text("dither", "0");
 
-    if options.ttl.is_some() {
-        let minutes = options.ttl.unwrap();
+    if let Some(minutes) = options.ttl {
         form = form.text("ttl", format!("{}", minutes));
     }
 
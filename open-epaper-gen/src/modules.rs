@@ -18,6 +18,7 @@ pub struct ViewOptions {
 }
 
 impl ViewOptions {
+    #[allow(dead_code)]
     fn none() -> Self {
         ViewOptions{
             ttl: None,
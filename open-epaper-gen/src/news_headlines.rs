@@ -12,22 +12,22 @@ use feed_rs::parser;
 use rand::{Rng};
 use log::{info};
 
-use crate::draw::{Surface, VStack, VAlign, HStack, Text, Edge, Spacer, View, Image, Font};
+use crate::draw::{Surface, VAlign, HStack, Text, Truncation, ShrinkToFit, FitSearch, Grid, GridCell, Track, Edge, Spacer, View, Image};
 use crate::modules::{InfoView, ViewOptions};
 
+/// The range of font sizes for headlines. Short headlines are drawn at the
+/// maximum size, long ones shrink down to the minimum before they're truncated.
+const MIN_HEADLINE_SIZE: f32 = 16.0;
+const MAX_HEADLINE_SIZE: f32 = 40.0;
+
 pub struct NewsHeadlines {
 }
 
 impl InfoView for NewsHeadlines {
     fn generate(&self, surface: &mut Surface) -> Result<ViewOptions> {
-        let surface_bounds = surface.bounds().clone();
+        let surface_bounds = surface.bounds();
 
-        if surface_bounds.width != 296 || surface_bounds.height != 128 {
-            bail!("Currently, NewsHeadlines only supports displays with \
-                  296x128 resolution");
-        }
-    
-        let news_outlets = vec![
+        let news_outlets = [
             NewsOutlet{
                 name: "Tagesschau".to_string(),
                 rss_endpoint: "https://www.tagesschau.de/index~rss2.xml".to_string(),
@@ -77,27 +77,26 @@ impl InfoView for NewsHeadlines {
             .ok_or(anyhow!("The first entry has no title."))?
             .content;
         
-        let mut screen = VStack::new();
-        let mut headline = Text::new(headline_text.to_string(), 20.0, Font::PlayfairDisplay);
+        // The headline takes up whatever space the bottom bar leaves, and picks
+        // its font size accordingly. Headlines that are too long even at the
+        // smallest size are cut short.
+        let mut screen = Grid::new(
+            vec![Track::Fraction(1)],
+            vec![Track::Fraction(1), Track::Content],
+        );
+        let mut headline = Text::new(headline_text.to_string(), MAX_HEADLINE_SIZE, "PlayfairDisplay");
         headline.wrap_text = true;
+        headline.shrink_to_fit = Some(ShrinkToFit {
+            min_size: MIN_HEADLINE_SIZE,
+            max_size: MAX_HEADLINE_SIZE,
+            search: FitSearch::Step(1.0),
+        });
+        headline.truncation = Truncation::Word;
         headline.padding(Edge::Top, 10);
         headline.padding(Edge::Left, 10);
         headline.padding(Edge::Right, 10);
         headline.padding(Edge::Bottom, 10);
 
-        let mut font_size = 40.0;
-        let max_headline_height = 128 - 30 /* bottom bar */ - 20 /* padding */;
-        let max_headline_width = 296 - 20 /* padding */;
-        loop {
-            headline.size = font_size;
-            let text_bounds = headline.bounds(&surface, surface_bounds.optimally_hinted());
-            if text_bounds.height < max_headline_height &&
-                text_bounds.width < max_headline_width {
-                break;
-            }
-            font_size = font_size - 1.0;
-        }
-
         let mut bottom_bar = HStack::new();
         bottom_bar.padding(Edge::Right, 10);
         bottom_bar.padding(Edge::Bottom, 10);
@@ -110,17 +109,17 @@ impl InfoView for NewsHeadlines {
 
         bottom_bar.views.push(Box::new(logo));
         bottom_bar.views.push(Box::new(Spacer::horizontal()));
-        bottom_bar.views.push(Box::new(Text::new(Local::now().format("%m-%d %H:%M").to_string(), 13.0, Font::Roboto)));
+        bottom_bar.views.push(Box::new(Text::new(Local::now().format("%m-%d %H:%M").to_string(), 13.0, "Roboto")));
 
-        screen.views.push(Box::new(headline));
-        screen.views.push(Box::new(Spacer::vertical()));
-        screen.views.push(Box::new(bottom_bar));
+        screen.cells.push(GridCell::new(Box::new(headline), 0, 0));
+        screen.cells.push(GridCell::new(Box::new(bottom_bar), 1, 0));
 
-        if screen.bounds(surface, surface_bounds) > surface_bounds {
-            bail!("Cannot fit the current view into the given space");
-        }
+        // Check everything fits before drawing, so the error says which view
+        // doesn't.
+        screen.layout(surface, surface_bounds)
+            .context("Cannot fit the current view into the given space")?;
 
-        screen.draw(surface, 0, 0, surface_bounds);
+        surface.draw_view(&screen, 0, 0, surface_bounds);
 
         Ok(ViewOptions{
             ttl: Some(5), // minutes
@@ -5,7 +5,6 @@ use clap::{Args, Parser, ValueEnum};
 use cargo_metadata::Metadata;
 use std::process::Command;
 use std::sync::OnceLock;
-use fs_extra;
 
 static METADATA: OnceLock<Metadata> = OnceLock::new();
 fn cargo_meta() -> &'static Metadata {